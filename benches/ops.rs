@@ -0,0 +1,68 @@
+//! `cargo bench`, the encrypted benches run on the toy curve with 4 blocks of
+//! `PARAM_MESSAGE_2_CARRY_2` so a sample takes seconds instead of hours on secp256k1.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ecdsa::{
+    ecdsa::ecdsa_sign_native,
+    helper::u256_from_hex_string,
+    ops::{
+        group_jacobian::scalar_mul,
+        mersenne::{mod_mersenne, mul_mod_mersenne},
+        secp256k1::prelude::PARAMS,
+        testcurve,
+    },
+    WINDOW,
+};
+use tfhe::{
+    integer::{keycache::IntegerKeyCache, IntegerKeyKind},
+    shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+};
+
+const NUM_BLOCK: usize = 4;
+
+fn bench_encrypted(c: &mut Criterion) {
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    let params = testcurve::PARAMS;
+    let p: u8 = 251;
+    let a = client_key.encrypt_radix(250u8, NUM_BLOCK);
+    let b = client_key.encrypt_radix(249u8, NUM_BLOCK);
+    let ab = client_key.encrypt_radix(250u16 * 249, NUM_BLOCK * 2);
+    let k = client_key.encrypt_radix(71u8, NUM_BLOCK);
+
+    let mut group = c.benchmark_group("encrypted");
+    group.sample_size(10);
+    group.bench_function("mul_mod_mersenne", |bench| {
+        bench.iter(|| mul_mod_mersenne::<NUM_BLOCK, _>(&a, &b, p, &server_key))
+    });
+    group.bench_function("mod_mersenne", |bench| {
+        bench.iter(|| mod_mersenne::<NUM_BLOCK, _>(&ab, p, &server_key))
+    });
+    group.bench_function("scalar_mul", |bench| {
+        bench.iter(|| {
+            scalar_mul::<WINDOW, NUM_BLOCK, _>(
+                &k,
+                params.generator.into(),
+                8,
+                params.p,
+                &server_key,
+            )
+        })
+    });
+    group.finish();
+}
+
+fn bench_native(c: &mut Criterion) {
+    let sk =
+        u256_from_hex_string("0xc9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+    let k =
+        u256_from_hex_string("0xa6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60");
+    let message =
+        u256_from_hex_string("0xaf2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf");
+    c.bench_function("ecdsa_sign_native", |bench| {
+        bench.iter(|| ecdsa_sign_native(black_box(sk), black_box(k), message, &PARAMS))
+    });
+}
+
+criterion_group!(benches, bench_encrypted, bench_native);
+criterion_main!(benches);
@@ -0,0 +1,241 @@
+//! blind Schnorr signatures over full points, s * G = R + e * X with e = H(R, X, m) % `n`.
+//! ECDSA's s = k^-1 * (m + r * sk) can't be unblinded without the signer's nonce, which in
+//! turn gives away sk, Schnorr's linear s = k + e * sk can.
+//!
+//! 1. the signer picks a fresh secret nonce k and sends the commitment R = k * G
+//! 2. the requester blinds R' = R + alpha * G + beta * X and sends e = H(R', X, m) + beta,
+//!    `blind_message_native`
+//! 3. the signer answers s = k + e * sk, `blind_sign_native`
+//! 4. the requester unblinds s' = s + alpha, `unblind_signature_native`, and (R', s') verifies
+//!    under X with `verify_blind_signature_native`
+//!
+//! the signer only sees R, e and s, which are independent of m, R' and s'. a nonce must never
+//! sign twice, and sessions should not run concurrently since the ROS attack forges a
+//! signature from enough parallel sessions.
+//! with FHE the signer keeps sk and k encrypted: R is `group_projective_scalar_mul_fixed_window`
+//! and s one `mul_mod` and `add_mod`, both decrypted by the key holder.
+
+use num_bigint::{BigInt, Sign};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    helper::{from_bigint_unchecked, to_bigint},
+    numeral::Numeral,
+    ops::{
+        curve::{is_on_curve_native, CurveParams},
+        group_jacobian::{group_affine_add_native, scalar_mul_native},
+        native::{add_mod_native, modulo_native, mul_mod_native, sub_mod_native},
+    },
+};
+
+/// secret of the requester, both uniformly random % `n` and fresh for every signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlindingFactor<P> {
+    /// alpha in [0, n - 1] added to the signer's s
+    pub alpha: P,
+    /// beta in [0, n - 1] added to the challenge
+    pub beta: P,
+}
+
+/// e = SHA256(R.x || R.y || X.x || X.y || m) % `n`, each value big-endian on the byte length
+/// of `p`
+fn challenge_native<P: Numeral>(
+    nonce_point: (P, P),
+    public_key: (P, P),
+    message: P,
+    params: &CurveParams<P>,
+) -> P {
+    let len = ((to_bigint(params.p).bits() + 7) / 8) as usize;
+    let mut hasher = Sha256::new();
+    for value in [
+        nonce_point.0,
+        nonce_point.1,
+        public_key.0,
+        public_key.1,
+        message,
+    ] {
+        let bytes = to_bigint(value).to_bytes_be().1;
+        hasher.update(vec![0u8; len - bytes.len()]);
+        hasher.update(bytes);
+    }
+    let e = BigInt::from_bytes_be(Sign::Plus, &hasher.finalize()) % to_bigint(params.n);
+    from_bigint_unchecked(&e)
+}
+
+/// blind `message` % `n` for the signer's commitment R = k * G.
+/// returns the challenge e to send to the signer and R' to keep for `unblind_signature_native`,
+/// `None` if R' is the identity and another blinding factor has to be picked.
+pub fn blind_message_native<P: Numeral>(
+    message: P,
+    commitment: (P, P),
+    public_key: (P, P),
+    blinding_factor: &BlindingFactor<P>,
+    params: &CurveParams<P>,
+) -> Option<(P, (P, P))> {
+    // R' = R + alpha * G + beta * X
+    let nonce_point = group_affine_add_native(
+        group_affine_add_native(
+            commitment,
+            scalar_mul_native(blinding_factor.alpha, params.generator, params),
+            params.a,
+            params.p,
+        ),
+        scalar_mul_native(blinding_factor.beta, public_key, params),
+        params.a,
+        params.p,
+    );
+    if nonce_point == (P::ZERO, P::ZERO) {
+        return None;
+    }
+    let message = modulo_native(message, params.n);
+    let e = challenge_native(nonce_point, public_key, message, params);
+
+    Some((
+        add_mod_native(e, blinding_factor.beta, params.n),
+        nonce_point,
+    ))
+}
+
+/// signer's answer s = k + e * sk % `n` to the blinded `challenge` for the commitment k * G
+pub fn blind_sign_native<P: Numeral>(sk: P, k: P, challenge: P, r_modulo: P) -> P {
+    assert!(
+        k != P::ZERO && k < r_modulo,
+        "nonce k must be in [1, n - 1]"
+    );
+    add_mod_native(k, mul_mod_native(challenge, sk, r_modulo), r_modulo)
+}
+
+/// turn the signer's `s` into the signature (R', s + alpha) over the original message
+pub fn unblind_signature_native<P: Numeral>(
+    s: P,
+    nonce_point: (P, P),
+    blinding_factor: &BlindingFactor<P>,
+    r_modulo: P,
+) -> ((P, P), P) {
+    (
+        nonce_point,
+        add_mod_native(s, blinding_factor.alpha, r_modulo),
+    )
+}
+
+/// s * G = R + H(R, X, m) * X for the unblinded `signature` (R, s) of `message` % `n`
+pub fn verify_blind_signature_native<P: Numeral>(
+    signature: ((P, P), P),
+    message: P,
+    public_key: (P, P),
+    params: &CurveParams<P>,
+) -> bool {
+    let (nonce_point, s) = signature;
+    if s >= params.n || !is_on_curve_native(nonce_point, params) {
+        return false;
+    }
+    let message = modulo_native(message, params.n);
+    let e = challenge_native(nonce_point, public_key, message, params);
+
+    // R = s * G - e * X
+    let expected = group_affine_add_native(
+        scalar_mul_native(s, params.generator, params),
+        scalar_mul_native(sub_mod_native(P::ZERO, e, params.n), public_key, params),
+        params.a,
+        params.p,
+    );
+    expected == nonce_point
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::OsRng, Rng};
+    use tfhe::integer::U256;
+
+    use crate::{
+        ecdsa::derive_public_key_native,
+        helper::u256_from_hex_string,
+        ops::{group_jacobian::scalar_mul_native, secp256k1::prelude::PARAMS, testcurve},
+    };
+
+    use super::{
+        blind_message_native, blind_sign_native, unblind_signature_native,
+        verify_blind_signature_native, BlindingFactor,
+    };
+
+    #[test]
+    fn correct_blind_signature_native() {
+        let params = testcurve::PARAMS;
+        let sk = 77;
+        let pk = derive_public_key_native(sk, &params);
+
+        for _ in 0..100 {
+            let message = OsRng.gen_range(0..params.n);
+            let k = OsRng.gen_range(1..params.n);
+            let blinding_factor = BlindingFactor {
+                alpha: OsRng.gen_range(0..params.n),
+                beta: OsRng.gen_range(0..params.n),
+            };
+
+            let commitment = scalar_mul_native(k, params.generator, &params);
+            let Some((challenge, nonce_point)) =
+                blind_message_native(message, commitment, pk, &blinding_factor, &params)
+            else {
+                continue;
+            };
+            let s = blind_sign_native(sk, k, challenge, params.n);
+            let signature = unblind_signature_native(s, nonce_point, &blinding_factor, params.n);
+
+            assert!(verify_blind_signature_native(
+                signature, message, pk, &params
+            ));
+        }
+    }
+
+    #[test]
+    fn correct_blind_signature_native_secp256k1() {
+        let params = *PARAMS;
+        let sk = u256_from_hex_string(
+            "c28a9f80738f770d527803a566cf6fc3edf6cea586c4fc4a5223a5ad797e1ac3",
+        );
+        let k = u256_from_hex_string(
+            "6f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8",
+        );
+        let message = u256_from_hex_string(
+            "4b688df40bcedbe641ddb16ff0a1842d9c67ea1c3bf63f3e0471baa664531d1a",
+        );
+        let blinding_factor = BlindingFactor {
+            alpha: u256_from_hex_string(
+                "0f3a7c2e91d4b6a8053e7f1c2d9b4a6e8c1f3d5b7a9e2c4f6a8b0d1e3f5a7c9b",
+            ),
+            beta: u256_from_hex_string(
+                "3c9e1f5a7b2d4c6e8f0a1b3d5c7e9f1a2b4c6d8e0f1a3b5c7d9e1f2a4b6c8d0e",
+            ),
+        };
+        let pk = derive_public_key_native(sk, &params);
+
+        let commitment = scalar_mul_native(k, params.generator, &params);
+        let (challenge, nonce_point) =
+            blind_message_native(message, commitment, pk, &blinding_factor, &params).unwrap();
+        let s = blind_sign_native(sk, k, challenge, params.n);
+        let signature = unblind_signature_native(s, nonce_point, &blinding_factor, params.n);
+
+        assert!(verify_blind_signature_native(
+            signature, message, pk, &params
+        ));
+        assert!(!verify_blind_signature_native(
+            signature,
+            message + U256::ONE,
+            pk,
+            &params
+        ));
+        // the signer's own view R, s doesn't verify
+        assert!(!verify_blind_signature_native(
+            (commitment, s),
+            message,
+            pk,
+            &params
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "nonce k must be in [1, n - 1]")]
+    fn reject_blind_sign_native_zero_nonce() {
+        blind_sign_native(77u8, 0, 5, testcurve::PARAMS.n);
+    }
+}
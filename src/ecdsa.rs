@@ -1,56 +1,517 @@
 use std::time::Instant;
 
+use hmac::{Hmac, Mac};
+use log::{debug, trace};
+use num_bigint::{BigInt, Sign};
 use rand::{rngs::OsRng, Rng};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use sha2::Sha256;
 use tfhe::{
+    core_crypto::prelude::Numeric,
     integer::{
         block_decomposition::{DecomposableInto, RecomposableFrom},
         keycache::IntegerKeyCache,
-        IntegerKeyKind, RadixCiphertext, ServerKey,
+        ClientKey, IntegerCiphertext, IntegerKeyKind, RadixCiphertext, ServerKey, U256,
     },
     shortint::parameters::PARAM_MESSAGE_2_CARRY_2,
 };
 
 use crate::{
-    helper::{format, read_client_key},
+    helper::{
+        format, from_bigint_unchecked, install_thread_pool, read_client_key, to_bigint,
+        u256_from_be_bytes, u256_to_be_bytes, HashToField,
+    },
     numeral::Numeral,
     ops::{
         add_mod,
+        curve::{is_on_curve_native, AffinePoint, CurveParams},
         group_jacobian::{
-            group_projective_add_projective, group_projective_add_projective_native,
+            group_affine_add_native, group_projective_add_projective_complete,
             group_projective_into_affine, group_projective_into_affine_inv,
-            group_projective_into_affine_native, group_projective_scalar_mul_constant,
-            group_projective_scalar_mul_constant_windowed, group_projective_scalar_mul_native,
+            group_projective_into_affine_native, group_projective_scalar_mul,
+            group_projective_scalar_mul_constant, group_projective_scalar_mul_constant_windowed,
+            group_projective_scalar_mul_fixed_window,
+            group_projective_scalar_mul_fixed_window_from, group_projective_scalar_mul_native,
+            precompute_generator_table, scalar_mul_native, shamir_mul_native,
         },
         inverse_mod, inverse_mods,
         mersenne::mod_mersenne,
-        modulo_fast, mul_mod,
-        native::{add_mod_native, inverse_mod_native, modulo_native, mul_mod_native},
+        modulo_fast, mul_mod, mul_mod_constant, mul_mod_with,
+        native::{
+            add_mod_native, inverse_mod_native, modulo_native, mul_mod_native, sqrt_mod_native,
+            square_mod_native, sub_mod_native,
+        },
+        primitive::parallel_fn,
+        selector, ReductionStrategy,
     },
     WINDOW,
 };
 
-/// perform homomorphic ECDSA signing on message `P` % `r` over secret key `secret_key` % `r` and nonce `k` % `r`
-/// with prime subgroup generator `x, y` % `q`
+/// window table of G for the homomorphic `k * G`. the homomorphic doubling is the a = 0
+/// formula, a curve like P-256 is only supported by the `_native` functions.
+fn generator_table<P: Numeral>(params: &CurveParams<P>) -> Vec<(P, P)> {
+    assert!(
+        params.a == P::ZERO,
+        "homomorphic doubling requires a curve with a = 0"
+    );
+    precompute_generator_table::<WINDOW, _>(params.generator, params.a, params.p)
+}
+
+/// encrypted intermediates of `ecdsa_sign_with_trace`, all % `n`
+#[derive(Clone)]
+pub struct SignTrace {
+    /// k^-1
+    pub k_inv: RadixCiphertext,
+    /// r * sk
+    pub r_sk: RadixCiphertext,
+    /// m + r * sk
+    pub m_r_sk: RadixCiphertext,
+}
+
+/// perform homomorphic ECDSA signing on message `P` % `n` over secret key `secret_key` % `n` and nonce `k` % `n`
+/// on curve `params`
 pub fn ecdsa_sign<const NB: usize, P: Numeral>(
     sk: &RadixCiphertext,
     k: &RadixCiphertext,
     message: P,
-    generator: (P, P),
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    ecdsa_sign_with_trace::<NB, _>(sk, k, message, params, server_key).0
+}
+
+/// homomorphic ECDSA signing like `ecdsa_sign` on the raw message `msg`, hashed % `n` with `hasher`
+/// e.g. `Sha256Hash` for Bitcoin or `Keccak256Hash` for Ethereum
+pub fn ecdsa_sign_hashed<const NB: usize, H: HashToField>(
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    msg: &[u8],
+    hasher: &H,
+    params: &CurveParams<U256>,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let message = hasher.hash(msg, params.n);
+    ecdsa_sign::<NB, _>(sk, k, message, params, server_key)
+}
+
+/// homomorphic ECDSA signing like `ecdsa_sign` that also returns the encrypted intermediates
+/// to decrypt and inspect them during development
+pub fn ecdsa_sign_with_trace<const NB: usize, P: Numeral>(
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    message: P,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> ((RadixCiphertext, RadixCiphertext), SignTrace) {
+    let table = generator_table(params);
+    let message = server_key.create_trivial_radix(message, NB);
+    install_thread_pool(|| {
+        ecdsa_sign_precomputed::<NB, _>(
+            sk, k, &message, &table, params.p, params.n, None, server_key,
+        )
+    })
+}
+
+/// perform homomorphic ECDSA signing on encrypted message % `n` over secret key `secret_key` % `n`
+/// and nonce `k` % `n` on curve `params`. the message hash stays private to the server.
+/// the only extra cost over `ecdsa_sign` is that `m + r * sk` adds 2 real ciphertexts instead of
+/// a trivial one, a single `add_mod` next to the thousands of mod ops of `k * G`.
+pub fn ecdsa_sign_private_msg<const NB: usize, P: Numeral>(
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    message: &RadixCiphertext,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let table = generator_table(params);
+    install_thread_pool(|| {
+        ecdsa_sign_precomputed::<NB, _>(
+            sk, k, message, &table, params.p, params.n, None, server_key,
+        )
+    })
+    .0
+}
+
+/// perform homomorphic ECDSA signing on multiple messages `P` % `n` over secret key `secret_key` % `n`
+/// with one nonce `k` % `n` per message on curve `params`.
+/// the generator table is computed once for the whole batch and the signatures are computed
+/// concurrently on the rayon thread pool. the speedup over calling `ecdsa_sign` N times comes
+/// from keeping all cores busy when a single signature does not saturate them.
+pub fn ecdsa_sign_batch<const NB: usize, P: Numeral>(
+    sk: &RadixCiphertext,
+    k: &[RadixCiphertext],
+    messages: &[P],
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> Vec<(RadixCiphertext, RadixCiphertext)> {
+    assert_eq!(k.len(), messages.len(), "one nonce per message is required");
+    let table = generator_table(params);
+    install_thread_pool(|| {
+        k.par_iter()
+            .zip(messages.par_iter())
+            .map(|(k, message)| {
+                let message = server_key.create_trivial_radix(*message, NB);
+                ecdsa_sign_precomputed::<NB, _>(
+                    sk, k, &message, &table, params.p, params.n, None, server_key,
+                )
+                .0
+            })
+            .collect()
+    })
+}
+
+/// lazily perform homomorphic ECDSA signing on a stream of nonce `k` % `n` and message `P` % `n`
+/// pairs over secret key `secret_key` % `n` on curve `params`.
+/// the generator table is computed once, each signature is computed when it is pulled so the
+/// caller controls the pace and can stop early. use `ecdsa_sign_batch` to sign concurrently.
+pub fn ecdsa_sign_iter<'a, const NB: usize, P: Numeral, I>(
+    sk: &'a RadixCiphertext,
+    nonces_and_messages: I,
+    params: &'a CurveParams<P>,
+    server_key: &'a ServerKey,
+) -> impl Iterator<Item = (RadixCiphertext, RadixCiphertext)> + 'a
+where
+    I: IntoIterator<Item = (RadixCiphertext, P)>,
+    I::IntoIter: 'a,
+{
+    let table = generator_table(params);
+    nonces_and_messages.into_iter().map(move |(k, message)| {
+        let message = server_key.create_trivial_radix(message, NB);
+        install_thread_pool(|| {
+            ecdsa_sign_precomputed::<NB, _>(
+                sk, &k, &message, &table, params.p, params.n, None, server_key,
+            )
+        })
+        .0
+    })
+}
+
+/// homomorphic signing configuration, holds the server key, the curve and the generator table
+/// so it is computed once for all the signatures. the defaults sign like `ecdsa_sign`.
+pub struct EcdsaSigner<'a, const NB: usize, P: Numeral> {
+    server_key: &'a ServerKey,
+    params: CurveParams<P>,
+    table: Vec<(P, P)>,
+    low_s: bool,
+    reduction: Option<ReductionStrategy>,
+}
+
+impl<'a, const NB: usize, P: Numeral> EcdsaSigner<'a, NB, P> {
+    pub fn new(params: &CurveParams<P>, server_key: &'a ServerKey) -> Self {
+        EcdsaSigner {
+            server_key,
+            params: *params,
+            table: generator_table(params),
+            low_s: false,
+            reduction: None,
+        }
+    }
+
+    /// normalize `s` into its low-S form like `ecdsa_sign_low_s`, off by default
+    pub fn low_s(mut self, low_s: bool) -> Self {
+        self.low_s = low_s;
+        self
+    }
+
+    /// reduction of the multiplications % `n`, `ReductionStrategy::for_modulus` by default
+    pub fn reduction(mut self, strategy: ReductionStrategy) -> Self {
+        self.reduction = Some(strategy);
+        self
+    }
+
+    /// sign public `message` % `n` over secret key `sk` % `n` and nonce `k` % `n`
+    pub fn sign(
+        &self,
+        sk: &RadixCiphertext,
+        k: &RadixCiphertext,
+        message: P,
+    ) -> (RadixCiphertext, RadixCiphertext) {
+        let message = self.server_key.create_trivial_radix(message, NB);
+        self.sign_private_msg(sk, k, &message)
+    }
+
+    /// sign encrypted `message` % `n` like `ecdsa_sign_private_msg`
+    pub fn sign_private_msg(
+        &self,
+        sk: &RadixCiphertext,
+        k: &RadixCiphertext,
+        message: &RadixCiphertext,
+    ) -> (RadixCiphertext, RadixCiphertext) {
+        install_thread_pool(|| {
+            let (r, s) = ecdsa_sign_precomputed::<NB, _>(
+                sk,
+                k,
+                message,
+                &self.table,
+                self.params.p,
+                self.params.n,
+                self.reduction,
+                self.server_key,
+            )
+            .0;
+            match self.low_s {
+                true => (r, normalize_s::<NB, _>(&s, self.params.n, self.server_key)),
+                false => (r, s),
+            }
+        })
+    }
+}
+
+/// progress of `ecdsa_sign_resumable` after a window of `k * G`, enough to resume the signing
+#[derive(Clone)]
+pub struct SignState {
+    /// accumulator of k * G in jacobian coordinates
+    pub point: (RadixCiphertext, RadixCiphertext, RadixCiphertext),
+    /// k >> `bit`, the bits of k still to add
+    pub scalar: RadixCiphertext,
+    /// next bit of k to add
+    pub bit: usize,
+}
+
+impl SignState {
+    /// serialize the state e.g. to a file so `resume_sign` can pick it up after a crash
+    pub fn save(&self) -> Vec<u8> {
+        bincode::serialize(&(&self.point, &self.scalar, self.bit as u64))
+            .expect("ciphertexts are serializable")
+    }
+
+    pub fn load(bytes: &[u8]) -> bincode::Result<Self> {
+        let (point, scalar, bit): (_, _, u64) = bincode::deserialize(bytes)?;
+        Ok(SignState {
+            point,
+            scalar,
+            bit: bit as usize,
+        })
+    }
+}
+
+/// homomorphic ECDSA signing like `ecdsa_sign` that hands the state to `on_step` after each
+/// window of `k * G`, the bulk of the signing time. save it to resume with `resume_sign`.
+pub fn ecdsa_sign_resumable<const NB: usize, P: Numeral, F: FnMut(&SignState) + Send>(
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    message: P,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+    on_step: F,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let state = SignState {
+        point: (
+            server_key.create_trivial_radix(0, NB),
+            server_key.create_trivial_radix(0, NB),
+            server_key.create_trivial_radix(0, NB),
+        ),
+        scalar: k.clone(),
+        bit: 0,
+    };
+    ecdsa_sign_from_state::<NB, _, _>(state, sk, k, message, params, server_key, on_step)
+}
+
+/// resume `ecdsa_sign_resumable` from a state saved with `SignState::save`, with the same
+/// `sk`, `k`, `message` and curve. `on_step` gets the states of the remaining windows.
+pub fn resume_sign<const NB: usize, P: Numeral, F: FnMut(&SignState) + Send>(
+    state: &[u8],
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    message: P,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+    on_step: F,
+) -> bincode::Result<(RadixCiphertext, RadixCiphertext)> {
+    let state = SignState::load(state)?;
+    Ok(ecdsa_sign_from_state::<NB, _, _>(
+        state, sk, k, message, params, server_key, on_step,
+    ))
+}
+
+fn ecdsa_sign_from_state<const NB: usize, P: Numeral, F: FnMut(&SignState) + Send>(
+    state: SignState,
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    message: P,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+    mut on_step: F,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let table = generator_table(params);
+    let message = server_key.create_trivial_radix(message, NB);
+    install_thread_pool(|| {
+        let point = group_projective_scalar_mul_fixed_window_from::<WINDOW, NB, _, _>(
+            state.point,
+            &state.scalar,
+            state.bit,
+            &table,
+            <P as Numeric>::BITS,
+            params.p,
+            server_key,
+            |point, scalar, bit| {
+                on_step(&SignState {
+                    point: point.clone(),
+                    scalar: scalar.clone(),
+                    bit,
+                })
+            },
+        );
+        ecdsa_sign_from_point::<NB, _>(point, sk, k, &message, params.p, params.n, None, server_key)
+            .0
+    })
+}
+
+/// first step of the signing that reveals the nonce point `R = k * G` and keeps `s` encrypted,
+/// e.g. to feed `s` into a later homomorphic computation while anyone can already check R.
+/// returns the encrypted affine R, the key holder decrypts it and hands r = R.x % n back to
+/// `ecdsa_sign_half`.
+///
+/// threat model: R is public in every ECDSA signature through r = R.x % n and the recovery id,
+/// revealing R.y on top says nothing more about k or sk. the server never decrypts, it learns
+/// r only when the key holder sends it back, and the signature is unusable until the key
+/// holder decrypts `s`.
+pub fn ecdsa_sign_half_point<const NB: usize, P: Numeral>(
+    k: &RadixCiphertext,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let table = generator_table(params);
+    install_thread_pool(|| {
+        let (x, y, z) = group_projective_scalar_mul_fixed_window::<WINDOW, NB, _>(
+            k, &table, params.p, server_key,
+        );
+        group_projective_into_affine::<NB, _>(&x, &y, &z, params.p, server_key)
+    })
+}
+
+/// second step of the signing started by `ecdsa_sign_half_point`, the encrypted
+/// s = k^-1 * (m + r * sk) for the public r = R.x % n the key holder decrypted.
+/// since r is public, r * sk is a multiplication by a constant.
+pub fn ecdsa_sign_half<const NB: usize, P: Numeral>(
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    r: P,
+    message: P,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    install_thread_pool(|| {
+        let (k_inv, mrsk) = rayon::join(
+            || inverse_mod::<NB, _>(k, params.n, server_key),
+            || {
+                let rsk =
+                    mul_mod_constant::<NB, _>(sk, modulo_native(r, params.n), params.n, server_key);
+                let message = server_key.create_trivial_radix(modulo_native(message, params.n), NB);
+                add_mod::<NB, _>(&message, &rsk, params.n, server_key)
+            },
+        );
+        mul_mod::<NB, _>(&k_inv, &mrsk, params.n, server_key)
+    })
+}
+
+/// homomorphic ECDSA signing with precomputed generator table from `precompute_generator_table`.
+/// `reduction` overrides the reduction of the multiplications % `r_modulo`.
+#[allow(clippy::too_many_arguments)]
+fn ecdsa_sign_precomputed<const NB: usize, P: Numeral>(
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    message: &RadixCiphertext,
+    table: &[(P, P)],
     q_modulo: P,
     r_modulo: P,
+    reduction: Option<ReductionStrategy>,
     server_key: &ServerKey,
-) -> (RadixCiphertext, RadixCiphertext) {
+) -> ((RadixCiphertext, RadixCiphertext), SignTrace) {
+    for (name, ct) in [("sk", sk), ("k", k), ("message", message)] {
+        debug_assert_eq!(
+            ct.blocks().len(),
+            NB,
+            "{} has {} blocks, expected NB = {}",
+            name,
+            ct.blocks().len(),
+            NB
+        );
+    }
     // (x, y) = k * G
-    println!("ECDSA sign start");
-    println!("Calculating (x, y) = k * G");
+    debug!("ECDSA sign start");
+    debug!("Calculating (x, y) = k * G");
     let ops_start = Instant::now();
-    let (x_proj, y_proj, z_proj) = group_projective_scalar_mul_constant_windowed::<WINDOW, NB, _>(
-        generator.0,
-        generator.1,
-        k,
-        q_modulo,
-        server_key,
+    let point =
+        group_projective_scalar_mul_fixed_window::<WINDOW, NB, _>(k, table, q_modulo, server_key);
+    let res = ecdsa_sign_from_point::<NB, _>(
+        point, sk, k, message, q_modulo, r_modulo, reduction, server_key,
     );
+
+    debug!(
+        "ECDSA sign end, done in {:.2}s",
+        ops_start.elapsed().as_secs_f64(),
+    );
+
+    res
+}
+
+/// r = R.x % `r_modulo` of the affine R.x % `q_modulo`, a single conditional subtraction
+/// when q < 2n like secp256k1
+fn x_mod_n<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    q_modulo: P,
+    r_modulo: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    if q_modulo > r_modulo && q_modulo <= P::TWO * r_modulo {
+        modulo_fast::<NB, _>(x, r_modulo, server_key)
+    } else {
+        mod_mersenne::<NB, _>(x, r_modulo, server_key)
+    }
+}
+
+/// homomorphic r = (k * G).x % `n` of encrypted nonce `k` % `n` on curve `params`, the part of
+/// the signing that does not depend on the message or the secret key, e.g. to precompute r.
+/// case curve a = 0
+pub fn compute_r<const NB: usize, P: Numeral>(
+    k: &RadixCiphertext,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let table = generator_table(params);
+    let (q_modulo, r_modulo) = (params.p, params.n);
+    install_thread_pool(|| {
+        let (x, y, z) = group_projective_scalar_mul_fixed_window::<WINDOW, NB, _>(
+            k, &table, q_modulo, server_key,
+        );
+        let z_inv = inverse_mod::<NB, _>(&z, q_modulo, server_key);
+        let (x, _y) =
+            group_projective_into_affine_inv::<NB, _>(&x, &y, &z_inv, q_modulo, server_key);
+        x_mod_n::<NB, _>(&x, q_modulo, r_modulo, server_key)
+    })
+}
+
+/// a * b % `r_modulo`, the group order n, for the scalar side of the signing like
+/// s = k^-1 * (m + r * sk). the field side % q goes through `mul_mod` directly, keeping the two
+/// apart here makes a stray q in the scalar products stand out. `reduction` overrides the
+/// reduction `mul_mod` picks from n.
+pub fn mul_mod_n<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    r_modulo: P,
+    reduction: Option<ReductionStrategy>,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    match reduction {
+        Some(strategy) => mul_mod_with::<NB, _>(a, b, r_modulo, strategy, server_key),
+        None => mul_mod::<NB, _>(a, b, r_modulo, server_key),
+    }
+}
+
+/// the signing after `k * G`, from `point` = k * G in jacobian coordinates % `q_modulo`
+#[allow(clippy::too_many_arguments)]
+fn ecdsa_sign_from_point<const NB: usize, P: Numeral>(
+    point: (RadixCiphertext, RadixCiphertext, RadixCiphertext),
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
+    message: &RadixCiphertext,
+    q_modulo: P,
+    r_modulo: P,
+    reduction: Option<ReductionStrategy>,
+    server_key: &ServerKey,
+) -> ((RadixCiphertext, RadixCiphertext), SignTrace) {
+    let (x_proj, y_proj, z_proj) = point;
     let (z_inv, k_inv) = rayon::join(
         || inverse_mod::<NB, _>(&z_proj, q_modulo, server_key),
         || inverse_mod::<NB, _>(k, r_modulo, server_key),
@@ -58,228 +519,1788 @@ pub fn ecdsa_sign<const NB: usize, P: Numeral>(
     let (x, y) =
         group_projective_into_affine_inv::<NB, _>(&x_proj, &y_proj, &z_inv, q_modulo, server_key);
     read_client_key(|client_key| {
-        println!("x = {}", P::decrypt(&x, client_key).format());
-        println!("y = {}", P::decrypt(&y, client_key).format());
+        trace!("x = {}", P::decrypt(&x, client_key).format());
+        trace!("y = {}", P::decrypt(&y, client_key).format());
     });
     // r = x
     // s = k^-1 * (m + r * sk)
-    let r = if q_modulo > r_modulo && q_modulo <= P::TWO * r_modulo {
-        modulo_fast::<NB, _>(&x, r_modulo, server_key)
-    } else {
-        mod_mersenne::<NB, _>(&x, r_modulo, server_key)
-    };
+    let r = x_mod_n::<NB, _>(&x, q_modulo, r_modulo, server_key);
     read_client_key(|client_key| {
-        println!("k^-1 = {}", P::decrypt(&k_inv, client_key).format());
+        trace!("k^-1 = {}", P::decrypt(&k_inv, client_key).format());
     });
-    let mrsk = add_mod::<NB, _>(
-        &server_key.create_trivial_radix(message, NB),
-        &mul_mod::<NB, _>(&r, sk, r_modulo, server_key),
-        r_modulo,
-        server_key,
-    );
-    let s = mul_mod::<NB, _>(&k_inv, &mrsk, r_modulo, server_key);
+    let rsk = mul_mod_n::<NB, _>(&r, sk, r_modulo, reduction, server_key);
+    let mrsk = add_mod::<NB, _>(message, &rsk, r_modulo, server_key);
+    let s = mul_mod_n::<NB, _>(&k_inv, &mrsk, r_modulo, reduction, server_key);
     read_client_key(|client_key| {
-        println!("r = {}", P::decrypt(&r, client_key).format());
-        println!("s = {}", P::decrypt(&s, client_key).format());
+        trace!("r = {}", P::decrypt(&r, client_key).format());
+        trace!("s = {}", P::decrypt(&s, client_key).format());
     });
 
-    println!(
-        "ECDSA sign end, done in {:.2}s",
-        ops_start.elapsed().as_secs_f64(),
-    );
+    (
+        (r, s),
+        SignTrace {
+            k_inv,
+            r_sk: rsk,
+            m_r_sk: mrsk,
+        },
+    )
+}
 
-    (r, s)
+/// ECDSA signing on message `P` % `n` over secret key `sk` % `n` and nonce `k` on curve `params`.
+/// the caller must supply k in [1, n - 1], k = 0 has no inverse and panics.
+pub fn ecdsa_sign_native<P: Numeral>(sk: P, k: P, message: P, params: &CurveParams<P>) -> (P, P) {
+    assert!(
+        k != P::ZERO && k < params.n,
+        "nonce k must be in [1, n - 1]"
+    );
+    let k_inv = inverse_mod_native(k, params.n);
+    ecdsa_sign_native_with_kinv(sk, k, k_inv, message, params)
 }
 
-/// verify ECDSA signature
-pub fn ecdsa_sign_native<P: Numeral>(
+/// ECDSA signing like `ecdsa_sign_native` with a precomputed `k_inv` = k^-1 % `n`
+/// to profile the rest of the signing without the inversion
+pub fn ecdsa_sign_native_with_kinv<P: Numeral>(
     sk: P,
     k: P,
+    k_inv: P,
     message: P,
-    generator: (P, P),
-    q_modulo: P,
-    r_modulo: P,
+    params: &CurveParams<P>,
 ) -> (P, P) {
+    let r_modulo = params.n;
+    debug_assert!(
+        mul_mod_native(k, k_inv, r_modulo) == P::ONE,
+        "k_inv is not the inverse of k"
+    );
     // (x, y) = k * G
-    let (x, y, z) = group_projective_scalar_mul_native(generator.0, generator.1, k, q_modulo);
-    let (x, _y) = group_projective_into_affine_native(x, y, z, q_modulo);
+    let point = scalar_mul_native(k, params.generator, params);
     // r = x
     // s = k^-1 * (m + r * sk)
-    let r = modulo_native(x, r_modulo);
-    let k_inv = inverse_mod_native(k, r_modulo);
+    let r = modulo_native(point.0, r_modulo);
     let mrsk = add_mod_native(message, mul_mod_native(r, sk, r_modulo), r_modulo);
     let s = mul_mod_native(k_inv, mrsk, r_modulo);
 
     (r, s)
 }
 
-pub fn ecdsa_verify_native<P: Numeral>(
-    signature: (P, P),
+/// derive homomorphic ECDSA public key `Q = sk * G` from secret key `sk` % `n` on curve
+/// `params`. returns the encrypted affine coordinates % `p`.
+pub fn derive_public_key<const NB: usize, P: Numeral>(
+    sk: &RadixCiphertext,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let table = generator_table(params);
+    let q_modulo = params.p;
+    debug!("Derive public key start");
+    let ops_start = Instant::now();
+    // (x, y) = sk * G
+    let (x_proj, y_proj, z_proj) =
+        group_projective_scalar_mul_fixed_window::<WINDOW, NB, _>(sk, &table, q_modulo, server_key);
+    let (x, y) =
+        group_projective_into_affine::<NB, _>(&x_proj, &y_proj, &z_proj, q_modulo, server_key);
+    read_client_key(|client_key| {
+        trace!("x = {}", P::decrypt(&x, client_key).format());
+        trace!("y = {}", P::decrypt(&y, client_key).format());
+    });
+
+    debug!(
+        "Derive public key end, done in {:.2}s",
+        ops_start.elapsed().as_secs_f64(),
+    );
+
+    (x, y)
+}
+
+/// derive ECDSA public key `Q = sk * G` on curve `params`
+pub fn derive_public_key_native<P: Numeral>(sk: P, params: &CurveParams<P>) -> (P, P) {
+    scalar_mul_native(sk, params.generator, params)
+}
+
+/// homomorphic ECDSA signing like `ecdsa_sign` with `s` normalized into its low-S form.
+pub fn ecdsa_sign_low_s<const NB: usize, P: Numeral>(
+    sk: &RadixCiphertext,
+    k: &RadixCiphertext,
     message: P,
-    public_key: (P, P),
-    generator: (P, P),
-    q_modulo: P,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let (r, s) = ecdsa_sign::<NB, _>(sk, k, message, params, server_key);
+    (r, normalize_s::<NB, _>(&s, params.n, server_key))
+}
+
+/// homomorphic low-S normalization of `s` % `r`
+/// s > r/2 ? r - s : s
+pub fn normalize_s<const NB: usize, P: Numeral>(
+    s: &RadixCiphertext,
     r_modulo: P,
-) -> bool {
-    if signature.0 >= r_modulo || signature.1 >= r_modulo {
-        return false;
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let (is_high, neg_s) = rayon::join(
+        || server_key.scalar_gt_parallelized(s, r_modulo >> 1),
+        || server_key.sub_parallelized(&server_key.create_trivial_radix(r_modulo, NB), s),
+    );
+    let mut radix_is_high: RadixCiphertext = is_high.into_radix(NB - 1, server_key);
+    let len = radix_is_high.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_high, len - 1);
+    let not_is_high =
+        server_key.sub_parallelized(&server_key.create_trivial_radix(1, 1), &radix_is_high);
+    selector(&neg_s, s, &radix_is_high, &not_is_high, server_key)
+}
+
+/// low-S normalization of `s` % `r`
+/// s > r/2 ? r - s : s
+pub fn normalize_s_native<P: Numeral>(s: P, r_modulo: P) -> P {
+    if s > r_modulo >> 1 {
+        r_modulo - s
+    } else {
+        s
     }
+}
 
-    let s_inv = inverse_mod_native(signature.1, r_modulo);
-    let u1 = mul_mod_native(message, s_inv, r_modulo);
-    let u2 = mul_mod_native(signature.0, s_inv, r_modulo);
-    let (x1, y1, z1) = group_projective_scalar_mul_native(generator.0, generator.1, u1, q_modulo);
-    let (x2, y2, z2) = group_projective_scalar_mul_native(public_key.0, public_key.1, u2, q_modulo);
-    let (x, y, z) = group_projective_add_projective_native(x1, y1, z1, x2, y2, z2, q_modulo);
-    let (x, _y) = group_projective_into_affine_native(x, y, z, q_modulo);
+/// serialize homomorphic ECDSA signature `r, s` e.g. to store or send it to the client
+pub fn serialize_signature(signature: &(RadixCiphertext, RadixCiphertext)) -> Vec<u8> {
+    bincode::serialize(signature).expect("radix ciphertexts are serializable")
+}
 
-    return signature.0 == modulo_native(x, r_modulo);
+/// deserialize homomorphic ECDSA signature `r, s` from `serialize_signature`
+pub fn deserialize_signature(bytes: &[u8]) -> bincode::Result<(RadixCiphertext, RadixCiphertext)> {
+    bincode::deserialize(bytes)
 }
 
-#[cfg(test)]
-mod tests {
-    use rand::{rngs::OsRng, Rng};
-    use tfhe::{integer::keycache::IntegerKeyCache, shortint::prelude::PARAM_MESSAGE_2_CARRY_2};
+/// SEC1 compressed public key, `0x02` for even y and `0x03` for odd y followed by big-endian x
+pub fn compress_pubkey(point: (U256, U256)) -> [u8; 33] {
+    let mut bytes = [0u8; 33];
+    bytes[0] = if point.1 & U256::ONE == U256::ONE {
+        0x03
+    } else {
+        0x02
+    };
+    bytes[1..].copy_from_slice(&u256_to_be_bytes(point.0));
+    bytes
+}
 
-    use crate::{
-        ecdsa::ecdsa_sign,
-        helper::set_client_key,
-        numeral::Numeral,
-        ops::group_jacobian::{
-            group_projective_double_native, group_projective_into_affine_native,
-            group_projective_scalar_mul_native,
-        },
+/// SEC1 compressed public key into affine point `x, y` % `q` on y^2 = x^3 + 7,
+/// `None` if the prefix is invalid or x is not on the curve
+pub fn decompress_pubkey(bytes: &[u8; 33], q_modulo: U256) -> Option<(U256, U256)> {
+    let odd = match bytes[0] {
+        0x02 => false,
+        0x03 => true,
+        _ => return None,
     };
+    let x = u256_from_be_bytes(&bytes[1..]);
+    if x >= q_modulo {
+        return None;
+    }
+    let x3 = mul_mod_native(square_mod_native(x, q_modulo), x, q_modulo);
+    let y2 = add_mod_native(x3, U256::from(7u8), q_modulo);
+    let mut y = sqrt_mod_native(y2, q_modulo)?;
+    if (y & U256::ONE == U256::ONE) != odd {
+        y = sub_mod_native(U256::ZERO, y, q_modulo);
+    }
 
-    use super::{ecdsa_sign_native, ecdsa_verify_native};
+    Some((x, y))
+}
 
-    #[test]
-    fn correct_ecdsa_sign_verify_native() {
-        let q_modulo: u8 = 211;
-        let gx: u8 = 4;
-        let gy: u8 = 156;
-        let r_modulo: u8 = 199;
+/// DER encoding of ECDSA signature `r, s` as SEQUENCE { INTEGER r, INTEGER s }
+pub fn signature_to_der(r: U256, s: U256) -> Vec<u8> {
+    let encode_integer = |a: U256| {
+        let bytes = u256_to_be_bytes(a);
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(31);
+        let mut res = vec![0x02];
+        // positive integers need a leading zero when the high bit is set
+        if bytes[start] & 0x80 != 0 {
+            res.push((32 - start + 1) as u8);
+            res.push(0);
+        } else {
+            res.push((32 - start) as u8);
+        }
+        res.extend_from_slice(&bytes[start..]);
+        res
+    };
+    let body = [encode_integer(r), encode_integer(s)].concat();
 
-        let sk = 111;
-        let k = 71;
-        let message = 89;
-        let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
-        let pk = group_projective_into_affine_native(
-            pk_projective.0,
-            pk_projective.1,
-            pk_projective.2,
-            q_modulo,
-        );
-        let (r, s) = ecdsa_sign_native(sk, k, message, (gx, gy), q_modulo, r_modulo);
-        let is_valid = ecdsa_verify_native((r, s), message, pk, (gx, gy), q_modulo, r_modulo);
-        assert!(is_valid, "ECDSA signature is invalid");
-    }
+    [vec![0x30, body.len() as u8], body].concat()
+}
 
-    #[test]
-    fn correct_ecdsa_sign_verify_native_random() {
-        let q_modulo: u8 = 211;
-        let gx: u8 = 4;
-        let gy: u8 = 156;
-        let r_modulo: u8 = 199;
+/// ECDSA signature `r, s` from strict DER encoding, `None` if the encoding is not minimal
+pub fn signature_from_der(der: &[u8]) -> Option<(U256, U256)> {
+    let decode_integer = |bytes: &[u8]| -> Option<(U256, usize)> {
+        if bytes.len() < 2 || bytes[0] != 0x02 {
+            return None;
+        }
+        let len = bytes[1] as usize;
+        let value = bytes.get(2..2 + len)?;
+        // no empty, negative or zero padded integers
+        if len == 0 || len > 33 || value[0] & 0x80 != 0 {
+            return None;
+        }
+        if len > 1 && value[0] == 0 && value[1] & 0x80 == 0 {
+            return None;
+        }
+        let value = if value[0] == 0 { &value[1..] } else { value };
+        if value.len() > 32 {
+            return None;
+        }
+        Some((u256_from_be_bytes(value), 2 + len))
+    };
 
-        let sk = OsRng.gen_range(1..r_modulo);
-        let k = OsRng.gen_range(1..r_modulo);
-        let message = OsRng.gen_range(1..r_modulo);
-        let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
-        let pk = group_projective_into_affine_native(
-            pk_projective.0,
-            pk_projective.1,
-            pk_projective.2,
-            q_modulo,
-        );
-        let (r, s) = ecdsa_sign_native(sk, k, message, (gx, gy), q_modulo, r_modulo);
-        let is_valid = ecdsa_verify_native((r, s), message, pk, (gx, gy), q_modulo, r_modulo);
-        assert!(is_valid, "ECDSA signature is invalid");
+    if der.len() < 2 || der[0] != 0x30 || der[1] as usize != der.len() - 2 {
+        return None;
+    }
+    let (r, r_len) = decode_integer(&der[2..])?;
+    let (s, s_len) = decode_integer(&der[2 + r_len..])?;
+    if 2 + r_len + s_len != der.len() {
+        return None;
     }
-}
 
-#[test]
-fn correct_ecdsa_sign_verify() {
-    let q_modulo: u8 = 211;
-    let gx: u8 = 4;
-    let gy: u8 = 156;
-    let r_modulo: u8 = 199;
+    Some((r, s))
+}
 
-    let sk = 111;
-    let k = 71;
-    let message = 89;
-    let (rx, ry) = ecdsa_sign_native(sk, k, message, (gx, gy), q_modulo, r_modulo);
+/// Ethereum `r || s || v` encoding of ECDSA signature `r, s` with recovery id `v` of
+/// `ecdsa_recover_native`, stored as 27 + v
+pub fn signature_to_eth_bytes(r: U256, s: U256, v: u8) -> [u8; 65] {
+    assert!(v < 2, "recovery id must be 0 or 1");
+    let mut bytes = [0u8; 65];
+    bytes[..32].copy_from_slice(&u256_to_be_bytes(r));
+    bytes[32..64].copy_from_slice(&u256_to_be_bytes(s));
+    bytes[64] = 27 + v;
+    bytes
+}
 
-    let (client_key, server_key) =
-        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
-    const NUM_BLOCK: usize = 4;
+/// ECDSA signature `r, s` and recovery id from `r || s || v`, `None` if v is not 27 or 28
+pub fn signature_from_eth_bytes(bytes: &[u8; 65]) -> Option<(U256, U256, u8)> {
+    let v = match bytes[64] {
+        27 | 28 => bytes[64] - 27,
+        _ => return None,
+    };
 
-    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
-    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+    Some((
+        u256_from_be_bytes(&bytes[..32]),
+        u256_from_be_bytes(&bytes[32..64]),
+        v,
+    ))
+}
 
-    let (enc_rx, enc_ry) = ecdsa_sign::<NUM_BLOCK, _>(
-        &enc_sk,
-        &enc_k,
-        message,
-        (gx, gy),
-        q_modulo,
-        r_modulo,
-        &server_key,
-    );
+/// deterministic nonce `k` % `r` from secret key `sk` % `r` and message `P` % `r`
+/// using the HMAC-DRBG construction of RFC 6979 with SHA-256.
+/// `message` is expected to be the message hash as an integer.
+pub fn rfc6979_nonce_native<P: Numeral>(sk: P, message: P, r_modulo: P) -> P {
+    let r_bigint = to_bigint(r_modulo);
+    let qlen = r_bigint.bits();
+    let rlen = ((qlen + 7) / 8) as usize;
+    let int2octets = |x: &BigInt| {
+        let bytes = x.to_bytes_be().1;
+        let mut res = vec![0u8; rlen - bytes.len()];
+        res.extend(bytes);
+        res
+    };
+    let bits2int = |bytes: &[u8]| {
+        let x = BigInt::from_bytes_be(Sign::Plus, bytes);
+        let blen = bytes.len() as u64 * 8;
+        if blen > qlen {
+            x >> (blen - qlen)
+        } else {
+            x
+        }
+    };
+    let hmac_sha256 = |key: &[u8], data: &[&[u8]]| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+        for d in data {
+            mac.update(d);
+        }
+        mac.finalize().into_bytes().to_vec()
+    };
 
-    assert_eq!(rx, u8::decrypt(&enc_rx, &client_key));
-    assert_eq!(ry, u8::decrypt(&enc_ry, &client_key));
+    let x = int2octets(&to_bigint(sk));
+    let h = int2octets(&(to_bigint(message) % &r_bigint));
+    let mut v = vec![0x01u8; 32];
+    let mut k = vec![0x00u8; 32];
+    k = hmac_sha256(&k, &[v.as_slice(), &[0x00], x.as_slice(), h.as_slice()]);
+    v = hmac_sha256(&k, &[v.as_slice()]);
+    k = hmac_sha256(&k, &[v.as_slice(), &[0x01], x.as_slice(), h.as_slice()]);
+    v = hmac_sha256(&k, &[v.as_slice()]);
 
-    let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
-    let pk = group_projective_into_affine_native(
-        pk_projective.0,
-        pk_projective.1,
-        pk_projective.2,
-        q_modulo,
-    );
-    let is_valid = ecdsa_verify_native((rx, ry), message, pk, (gx, gy), q_modulo, r_modulo);
-    assert!(is_valid, "ECDSA signature is invalid");
+    // generate candidates until k is in [1, n - 1]
+    loop {
+        let mut t = vec![];
+        while (t.len() as u64) * 8 < qlen {
+            v = hmac_sha256(&k, &[v.as_slice()]);
+            t.extend_from_slice(&v);
+        }
+        let nonce = bits2int(&t);
+        if nonce >= BigInt::from(1) && nonce < r_bigint {
+            return from_bigint_unchecked(&nonce);
+        }
+        k = hmac_sha256(&k, &[v.as_slice(), &[0x00]]);
+        v = hmac_sha256(&k, &[v.as_slice()]);
+    }
 }
 
-#[test]
-fn correct_ecdsa_sign_verify_random() {
-    let q_modulo: u8 = 211;
-    let gx: u8 = 4;
-    let gy: u8 = 156;
-    let r_modulo: u8 = 199;
+/// ECDSA signing with deterministic nonce from `rfc6979_nonce_native`.
+/// the homomorphic `ecdsa_sign` still requires an externally encrypted nonce
+/// since evaluating HMAC-SHA256 under FHE is out of scope.
+pub fn ecdsa_sign_deterministic_native<P: Numeral>(
+    sk: P,
+    message: P,
+    params: &CurveParams<P>,
+) -> (P, P) {
+    let k = rfc6979_nonce_native(sk, message, params.n);
+    ecdsa_sign_native(sk, k, message, params)
+}
+
+/// `counter`-th nonce in [1, n - 1] derived from `nonce_seed` by `ecdsa_sign_native_retry`
+/// as 1 + HMAC-SHA256(seed, counter) % (n - 1)
+pub fn retry_nonce_native<P: Numeral>(nonce_seed: P, counter: u32, r_modulo: P) -> P {
+    let seed = to_bigint(nonce_seed).to_bytes_be().1;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&seed).expect("HMAC accepts keys of any size");
+    mac.update(&counter.to_be_bytes());
+    let digest = BigInt::from_bytes_be(Sign::Plus, &mac.finalize().into_bytes());
+    from_bigint_unchecked(&(digest % (to_bigint(r_modulo) - 1) + 1))
+}
+
+/// ECDSA signing that derives nonces from `nonce_seed` with `retry_nonce_native` until
+/// r != 0 and s != 0. the homomorphic `ecdsa_sign` cannot branch on encrypted r and s,
+/// it would have to compute both candidates and `selector` between them or let the client
+/// decrypt, check and ask again with a new encrypted nonce.
+pub fn ecdsa_sign_native_retry<P: Numeral>(
+    sk: P,
+    message: P,
+    nonce_seed: P,
+    params: &CurveParams<P>,
+) -> (P, P) {
+    (0..)
+        .map(|counter| retry_nonce_native(nonce_seed, counter, params.n))
+        .map(|k| ecdsa_sign_native(sk, k, message, params))
+        .find(|(r, s)| *r != P::ZERO && *s != P::ZERO)
+        .expect("a valid nonce within 2^32 tries")
+}
+
+/// perform homomorphic ECDSA verification of signature `r, s` % `n` on message `P` % `n`
+/// against public key `x, y` % `p` on curve `params`, a curve with a = 0.
+/// returns an encrypted bit, 1 if the signature is valid and 0 otherwise.
+pub fn ecdsa_verify<const NB: usize, P: Numeral>(
+    signature: (&RadixCiphertext, &RadixCiphertext),
+    message: P,
+    public_key: (&RadixCiphertext, &RadixCiphertext),
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    assert!(
+        params.a == P::ZERO,
+        "homomorphic doubling requires a curve with a = 0"
+    );
+    let CurveParams {
+        generator,
+        p: q_modulo,
+        n: r_modulo,
+        ..
+    } = *params;
+    debug!("ECDSA verify start");
+    let ops_start = Instant::now();
+    let (r, s) = signature;
+    // reject r = 0, s = 0, r >= n and s >= n
+    let ((is_r_non_zero, is_s_non_zero), (is_r_in_range, is_s_in_range)) = rayon::join(
+        || {
+            rayon::join(
+                || server_key.scalar_ne_parallelized(r, 0),
+                || server_key.scalar_ne_parallelized(s, 0),
+            )
+        },
+        || {
+            rayon::join(
+                || server_key.scalar_lt_parallelized(r, r_modulo),
+                || server_key.scalar_lt_parallelized(s, r_modulo),
+            )
+        },
+    );
+    // u1 = m * s^-1
+    // u2 = r * s^-1
+    debug!("Calculating u1 = m * s^-1, u2 = r * s^-1");
+    let s_inv = inverse_mod::<NB, _>(s, r_modulo, server_key);
+    let (u1, u2) = rayon::join(
+        || {
+            mul_mod::<NB, _>(
+                &server_key.create_trivial_radix(message, NB),
+                &s_inv,
+                r_modulo,
+                server_key,
+            )
+        },
+        || mul_mod::<NB, _>(r, &s_inv, r_modulo, server_key),
+    );
+    // (x, y) = u1 * G + u2 * Q
+    debug!("Calculating (x, y) = u1 * G + u2 * Q");
+    let ((x1, y1, z1), (x2, y2, z2)) = rayon::join(
+        || {
+            group_projective_scalar_mul_constant_windowed::<WINDOW, NB, _>(
+                generator.0,
+                generator.1,
+                &u1,
+                q_modulo,
+                server_key,
+            )
+        },
+        || {
+            group_projective_scalar_mul::<NB, _>(
+                public_key.0,
+                public_key.1,
+                &server_key.create_trivial_radix(1, NB),
+                &u2,
+                q_modulo,
+                server_key,
+            )
+        },
+    );
+    // u1 * G and u2 * Q are equal or inverses for some signatures
+    let (x_proj, y_proj, z_proj) = group_projective_add_projective_complete::<NB, _>(
+        &x1, &y1, &z1, &x2, &y2, &z2, q_modulo, server_key,
+    );
+    let (x, _y) =
+        group_projective_into_affine::<NB, _>(&x_proj, &y_proj, &z_proj, q_modulo, server_key);
+    // valid = r == x mod n
+    let x = x_mod_n::<NB, _>(&x, q_modulo, r_modulo, server_key);
+    let is_x_eq_r = server_key.eq_parallelized(&x, r);
+
+    let flags = [
+        is_r_non_zero,
+        is_s_non_zero,
+        is_r_in_range,
+        is_s_in_range,
+        is_x_eq_r,
+    ]
+    .into_iter()
+    .map(|flag| {
+        let mut radix_flag: RadixCiphertext = flag.into_radix(NB - 1, server_key);
+        let len = radix_flag.blocks().len();
+        server_key.trim_radix_blocks_msb_assign(&mut radix_flag, len - 1);
+        radix_flag
+    })
+    .collect::<Vec<_>>();
+    let is_valid = parallel_fn(&flags, |a, b| server_key.bitand_parallelized(a, b));
+    read_client_key(|client_key| {
+        trace!("is valid = {}", u8::decrypt(&is_valid, client_key));
+    });
+
+    debug!(
+        "ECDSA verify end, done in {:.2}s",
+        ops_start.elapsed().as_secs_f64(),
+    );
+
+    is_valid
+}
+
+/// verify ECDSA signature on curve `params`
+pub fn ecdsa_verify_native<P: Numeral>(
+    signature: (P, P),
+    message: P,
+    public_key: (P, P),
+    params: &CurveParams<P>,
+) -> bool {
+    let r_modulo = params.n;
+    if signature.0 == P::ZERO || signature.1 == P::ZERO {
+        return false;
+    }
+    if signature.0 >= r_modulo || signature.1 >= r_modulo {
+        return false;
+    }
+
+    let s_inv = inverse_mod_native(signature.1, r_modulo);
+    let u1 = mul_mod_native(message, s_inv, r_modulo);
+    let u2 = mul_mod_native(signature.0, s_inv, r_modulo);
+    // (x, y) = u1 * G + u2 * Q, the identity is (0, 0) and never matches r != 0
+    let (x, _y) = shamir_mul_native(u1, u2, params.generator, public_key, params);
+
+    return signature.0 == modulo_native(x, r_modulo);
+}
+
+/// panics with the failing check if `r, s` is not a valid signature of `message` under
+/// `public_key` on curve `params`, for tests. unlike asserting `ecdsa_verify_native` it tells an
+/// out of range r or s or a public key off the curve from a wrong signature and prints the
+/// recomputed `(u1 * G + u2 * Q).x` against r.
+pub fn assert_signature_valid_native<P: Numeral>(
+    public_key: (P, P),
+    message: P,
+    r: P,
+    s: P,
+    params: &CurveParams<P>,
+) {
+    let r_modulo = params.n;
+    assert!(
+        r != P::ZERO && r < r_modulo,
+        "r = {} is not in [1, n - 1] with n = {}",
+        r.format(),
+        r_modulo.format()
+    );
+    assert!(
+        s != P::ZERO && s < r_modulo,
+        "s = {} is not in [1, n - 1] with n = {}",
+        s.format(),
+        r_modulo.format()
+    );
+    assert!(
+        is_on_curve_native(public_key, params),
+        "public key ({}, {}) is not on the curve",
+        public_key.0.format(),
+        public_key.1.format()
+    );
+
+    // R = u1 * G + u2 * Q with u1 = m * s^-1 and u2 = r * s^-1
+    let s_inv = inverse_mod_native(s, r_modulo);
+    let u1 = mul_mod_native(modulo_native(message, r_modulo), s_inv, r_modulo);
+    let u2 = mul_mod_native(r, s_inv, r_modulo);
+    let r_point = group_affine_add_native(
+        scalar_mul_native(u1, params.generator, params),
+        scalar_mul_native(u2, public_key, params),
+        params.a,
+        params.p,
+    );
+    assert!(
+        r_point != (P::ZERO, P::ZERO),
+        "u1 * G + u2 * Q is the identity for signature ({}, {}) of message {}",
+        r.format(),
+        s.format(),
+        message.format()
+    );
+    assert!(
+        modulo_native(r_point.0, r_modulo) == r,
+        "signature ({}, {}) of message {} does not verify under public key ({}, {}): \
+         (u1 * G + u2 * Q).x = {} is not r mod n",
+        r.format(),
+        s.format(),
+        message.format(),
+        public_key.0.format(),
+        public_key.1.format(),
+        r_point.0.format()
+    );
+}
+
+/// recovery id of `ecdsa_recover_native` from `R = k * G`: bit 0 is the parity of `R.y`
+/// and bit 1 is set when `R.x >= n` so that r = R.x - n
+pub fn recovery_id_native<P: Numeral>(r_point: (P, P), r_modulo: P) -> u8 {
+    let (rx, ry) = r_point;
+    let parity = (ry.bitand(P::ONE) == P::ONE) as u8;
+    let overflow = (rx >= r_modulo) as u8;
+    parity | (overflow << 1)
+}
+
+/// homomorphic recovery id from the affine `R.y`, `R.x` % `q` of `k * G`, see `recovery_id_native`.
+/// decrypts to 0..=3 alongside `r, s` for ecrecover.
+pub fn compute_recovery_bits<const NB: usize, P: Numeral>(
+    ry: &RadixCiphertext,
+    rx: &RadixCiphertext,
+    r_modulo: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let (parity, overflow) = rayon::join(
+        || server_key.scalar_bitand_parallelized(ry, 1),
+        || {
+            let is_ge = server_key.scalar_ge_parallelized(rx, r_modulo);
+            let overflow: RadixCiphertext = is_ge.into_radix(NB - 1, server_key);
+            server_key.scalar_left_shift_parallelized(&overflow, 1)
+        },
+    );
+    server_key.add_parallelized(&parity, &overflow)
+}
+
+/// recover ECDSA public key from signature `r, s` % `n` on `message` % `n` on curve `params`
+/// like Ethereum's ecrecover.
+/// `recovery_id` bit 0 is the parity of `R.y` and bit 1 is set when `R.x = r + n`.
+pub fn ecdsa_recover_native<P: Numeral>(
+    signature: (P, P),
+    recovery_id: u8,
+    message: P,
+    params: &CurveParams<P>,
+) -> Option<(P, P)> {
+    let CurveParams {
+        a,
+        b,
+        p: q_modulo,
+        n: r_modulo,
+        generator,
+    } = *params;
+    let (r, s) = signature;
+    if r == P::ZERO || s == P::ZERO || r >= r_modulo || s >= r_modulo || recovery_id > 3 {
+        return None;
+    }
+
+    // R.x = r or r + n
+    let x = if recovery_id & 2 == 0 {
+        r
+    } else if q_modulo > r_modulo && r < q_modulo - r_modulo {
+        r + r_modulo
+    } else {
+        return None;
+    };
+    if x >= q_modulo {
+        return None;
+    }
+    // R.y = sqrt(x^3 + a*x + b)
+    let x3 = mul_mod_native(square_mod_native(x, q_modulo), x, q_modulo);
+    let ax = mul_mod_native(a, x, q_modulo);
+    let y2 = add_mod_native(add_mod_native(x3, ax, q_modulo), b, q_modulo);
+    let mut y = sqrt_mod_native(y2, q_modulo)?;
+    if (y.bitand(P::ONE) == P::ONE) != (recovery_id & 1 == 1) {
+        y = sub_mod_native(P::ZERO, y, q_modulo);
+    }
+
+    // Q = r^-1 * (s * R - m * G) = (s * r^-1) * R + (-m * r^-1) * G
+    let r_inv = inverse_mod_native(r, r_modulo);
+    let u1 = sub_mod_native(
+        P::ZERO,
+        mul_mod_native(modulo_native(message, r_modulo), r_inv, r_modulo),
+        r_modulo,
+    );
+    let u2 = mul_mod_native(s, r_inv, r_modulo);
+    let public_key = group_affine_add_native(
+        scalar_mul_native(u1, generator, params),
+        scalar_mul_native(u2, (x, y), params),
+        a,
+        q_modulo,
+    );
+    if public_key == (P::ZERO, P::ZERO) {
+        return None;
+    }
+
+    Some(public_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use log::{log_enabled, Level};
+    use rand::{rngs::OsRng, Rng};
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, U256},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use crate::{
+        ecdsa::ecdsa_sign,
+        helper::{set_client_key, u256_from_decimal_string, u256_from_hex_string},
+        numeral::Numeral,
+        ops::{
+            curve::CurveParams,
+            group_jacobian::{
+                group_projective_double_native, group_projective_into_affine_native,
+                group_projective_scalar_mul_native,
+            },
+            native::inverse_mod_native,
+            secp256k1::{
+                self,
+                prelude::{FQ_MODULO, FR_MODULO, GENERATOR},
+            },
+            secp256r1,
+        },
+    };
+
+    use super::{
+        assert_signature_valid_native, compress_pubkey, decompress_pubkey,
+        derive_public_key_native, ecdsa_recover_native, ecdsa_sign_deterministic_native,
+        ecdsa_sign_native, ecdsa_sign_native_retry, ecdsa_sign_native_with_kinv,
+        ecdsa_verify_native, normalize_s_native, recovery_id_native, retry_nonce_native,
+        rfc6979_nonce_native, signature_from_der, signature_from_eth_bytes, signature_to_der,
+        signature_to_eth_bytes,
+    };
+
+    #[test]
+    fn correct_ecdsa_sign_verify_native() {
+        let params = crate::ops::testcurve::PARAMS;
+        let q_modulo = params.p;
+        let (gx, gy) = params.generator;
+
+        let sk = 77;
+        let k = 35;
+        let message = 89;
+        let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
+        let pk = group_projective_into_affine_native(
+            pk_projective.0,
+            pk_projective.1,
+            pk_projective.2,
+            q_modulo,
+        );
+        let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+        let is_valid = ecdsa_verify_native((r, s), message, pk, &params);
+        assert!(is_valid, "ECDSA signature is invalid");
+    }
+
+    #[test]
+    fn correct_ecdsa_sign_native_with_kinv() {
+        let params = crate::ops::testcurve::PARAMS;
+        for _ in 0..32 {
+            let sk = OsRng.gen_range(1..params.n);
+            let k = OsRng.gen_range(1..params.n);
+            let message = OsRng.gen_range(0..params.n);
+            let k_inv = inverse_mod_native(k, params.n);
+            assert_eq!(
+                ecdsa_sign_native_with_kinv(sk, k, k_inv, message, &params),
+                ecdsa_sign_native(sk, k, message, &params)
+            );
+        }
+    }
+
+    #[test]
+    fn correct_assert_signature_valid_native() {
+        let params = crate::ops::testcurve::PARAMS;
+        let pk = derive_public_key_native(77u8, &params);
+        assert_signature_valid_native(pk, 89, 15, 48, &params);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not verify under public key (29, 65)")]
+    fn reject_assert_signature_valid_native_wrong_message() {
+        let params = crate::ops::testcurve::PARAMS;
+        assert_signature_valid_native((29, 65), 90, 15, 48, &params);
+    }
+
+    #[test]
+    #[should_panic(expected = "s = 109 is not in [1, n - 1] with n = 109")]
+    fn reject_assert_signature_valid_native_s_out_of_range() {
+        let params = crate::ops::testcurve::PARAMS;
+        assert_signature_valid_native((29, 65), 89, 15, 109, &params);
+    }
+
+    #[test]
+    fn correct_ecdsa_sign_native_retry() {
+        let params = crate::ops::testcurve::PARAMS;
+        let sk = 77;
+        let seed = 42;
+        let pk = derive_public_key_native(sk, &params);
+
+        // s = k^-1 * (m + r * sk) = 0 for the first nonce with m = -r * sk
+        let k0 = retry_nonce_native(seed, 0, params.n);
+        let (r0, _) = ecdsa_sign_native(sk, k0, 0, &params);
+        let message =
+            (params.n as u16 - (r0 as u16 * sk as u16) % params.n as u16) as u8 % params.n;
+        assert_eq!(ecdsa_sign_native(sk, k0, message, &params).1, 0);
+
+        let signature = ecdsa_sign_native_retry(sk, message, seed, &params);
+        let k1 = retry_nonce_native(seed, 1, params.n);
+        assert_ne!(k0, k1);
+        assert_eq!(signature, ecdsa_sign_native(sk, k1, message, &params));
+        assert_signature_valid_native(pk, message, signature.0, signature.1, &params);
+
+        // every message gets a valid signature from nonces in [1, n - 1]
+        for message in 0..params.n {
+            let signature = ecdsa_sign_native_retry(sk, message, seed, &params);
+            assert!(signature.0 != 0 && signature.1 != 0);
+            for counter in 0..4 {
+                let k = retry_nonce_native(seed, counter, params.n);
+                assert!(k >= 1 && k < params.n);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "nonce k must be in [1, n - 1]")]
+    fn reject_ecdsa_sign_native_zero_nonce() {
+        let params = crate::ops::testcurve::PARAMS;
+        ecdsa_sign_native(77, 0, 89, &params);
+    }
+
+    #[test]
+    #[should_panic(expected = "nonce k must be in [1, n - 1]")]
+    fn reject_ecdsa_sign_native_nonce_n() {
+        let params = crate::ops::testcurve::PARAMS;
+        ecdsa_sign_native(77, params.n, 89, &params);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "k_inv is not the inverse of k")]
+    fn reject_ecdsa_sign_native_with_wrong_kinv() {
+        let params = crate::ops::testcurve::PARAMS;
+        ecdsa_sign_native_with_kinv(5, 7, 7, 10, &params);
+    }
+
+    #[test]
+    fn correct_ecdsa_sign_verify_native_random() {
+        let params = crate::ops::testcurve::PARAMS;
+        let (q_modulo, r_modulo) = (params.p, params.n);
+        let (gx, gy) = params.generator;
+
+        let sk = OsRng.gen_range(1..r_modulo);
+        let k = OsRng.gen_range(1..r_modulo);
+        let message = OsRng.gen_range(1..r_modulo);
+        let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
+        let pk = group_projective_into_affine_native(
+            pk_projective.0,
+            pk_projective.1,
+            pk_projective.2,
+            q_modulo,
+        );
+        let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+        let is_valid = ecdsa_verify_native((r, s), message, pk, &params);
+        assert!(is_valid, "ECDSA signature is invalid");
+    }
+
+    #[test]
+    fn correct_derive_public_key_native() {
+        let params = crate::ops::testcurve::PARAMS;
+        let r_modulo = params.n;
+        let (gx, gy) = params.generator;
+
+        let sk = OsRng.gen_range(1..r_modulo);
+        let k = OsRng.gen_range(1..r_modulo);
+        let message = OsRng.gen_range(1..r_modulo);
+        let pk = derive_public_key_native(sk, &params);
+        assert_eq!(derive_public_key_native(1, &params), (gx, gy));
+
+        let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+        let is_valid = ecdsa_verify_native((r, s), message, pk, &params);
+        assert!(is_valid, "ECDSA signature is invalid");
+    }
+
+    #[test]
+    fn correct_ecdsa_recover_native() {
+        let params = crate::ops::testcurve::PARAMS;
+        let r_modulo = params.n;
+
+        let sk = OsRng.gen_range(1..r_modulo);
+        let k = OsRng.gen_range(1..r_modulo);
+        let message = OsRng.gen_range(1..r_modulo);
+        let pk = derive_public_key_native(sk, &params);
+        let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+
+        let recovery_id = recovery_id_native(derive_public_key_native(k, &params), r_modulo);
+        let recovered = ecdsa_recover_native((r, s), recovery_id, message, &params);
+        assert_eq!(recovered, Some(pk));
+    }
+
+    #[test]
+    fn reject_invalid_ecdsa_recover_native() {
+        let params = crate::ops::testcurve::PARAMS;
+
+        // x = 3 is not on the curve, 3^3 + 29 is a non-residue mod 127
+        assert_eq!(ecdsa_recover_native((3, 5), 0, 89, &params), None);
+        // r + n >= q
+        assert_eq!(ecdsa_recover_native((50, 5), 2, 89, &params), None);
+        assert_eq!(ecdsa_recover_native((0, 5), 0, 89, &params), None);
+    }
+
+    #[test]
+    fn correct_ecdsa_sign_native_secp256r1() {
+        // RFC 6979 A.2.5, P-256 with SHA-256 and message "sample"
+        let params = *secp256r1::prelude::PARAMS;
+        let sk = u256_from_hex_string(
+            "0xc9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+        );
+        let k = u256_from_hex_string(
+            "0xa6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+        );
+        let message = u256_from_hex_string(
+            "0xaf2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf",
+        );
+        let expected = (
+            u256_from_hex_string(
+                "0xefd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+            ),
+            u256_from_hex_string(
+                "0xf7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+            ),
+        );
+
+        assert_eq!(ecdsa_sign_native(sk, k, message, &params), expected);
+        assert_eq!(rfc6979_nonce_native(sk, message, params.n), k);
+        assert_eq!(
+            ecdsa_sign_deterministic_native(sk, message, &params),
+            expected
+        );
+        // a = -3, verification adds through the affine formulas of the curve
+        let pk = derive_public_key_native(sk, &params);
+        assert!(ecdsa_verify_native(expected, message, pk, &params));
+        assert!(!ecdsa_verify_native(
+            expected,
+            message + U256::ONE,
+            pk,
+            &params
+        ));
+    }
+
+    #[test]
+    fn correct_compress_pubkey() {
+        let expected = [
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+            "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "0379be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        ];
+        let sks = [
+            U256::from(1u8),
+            U256::from(2u8),
+            U256::from(3u8),
+            *FR_MODULO - U256::ONE,
+        ];
+        for (sk, expected) in sks.into_iter().zip(expected) {
+            let pk = derive_public_key_native(sk, &secp256k1::prelude::PARAMS);
+            let compressed = compress_pubkey(pk);
+            assert_eq!(hex::encode(compressed), expected);
+            assert_eq!(decompress_pubkey(&compressed, *FQ_MODULO), Some(pk));
+        }
+    }
+
+    #[test]
+    fn reject_invalid_compressed_pubkey() {
+        let mut compressed = compress_pubkey(*GENERATOR);
+        compressed[0] = 0x04;
+        assert_eq!(decompress_pubkey(&compressed, *FQ_MODULO), None);
+
+        // 5^3 + 7 is a non-residue mod q
+        let mut compressed = [0u8; 33];
+        compressed[0] = 0x02;
+        compressed[32] = 5;
+        assert_eq!(decompress_pubkey(&compressed, *FQ_MODULO), None);
+
+        // x >= q
+        let mut compressed = [0xffu8; 33];
+        compressed[0] = 0x02;
+        assert_eq!(decompress_pubkey(&compressed, *FQ_MODULO), None);
+    }
+
+    #[test]
+    fn correct_signature_der() {
+        let vectors = [
+            (
+                "0x4e45e16932b8af514961a1d3a1a25fdf3f4f7732e9d624c6c61548ab5fb8cd41",
+                "0x181522ec8eca07de4860a4acdd12909d831cc56cbbac4622082221a8768d1d09",
+                "304402204e45e16932b8af514961a1d3a1a25fdf3f4f7732e9d624c6c61548ab5fb8cd410220181522ec8eca07de4860a4acdd12909d831cc56cbbac4622082221a8768d1d09",
+            ),
+            (
+                "0x884d142d86652a3f47ba4746ec719bbfbd040a570b1deccbb6498c75c4ae24cb",
+                "0x4b9f039ff08df09cbe9f6addac960298cad530a863ea8f53982c09db8f6e3813",
+                "3045022100884d142d86652a3f47ba4746ec719bbfbd040a570b1deccbb6498c75c4ae24cb02204b9f039ff08df09cbe9f6addac960298cad530a863ea8f53982c09db8f6e3813",
+            ),
+            ("0x01", "0x80", "300702010102020080"),
+        ];
+        for (r, s, der) in vectors {
+            let (r, s) = (u256_from_hex_string(r), u256_from_hex_string(s));
+            assert_eq!(hex::encode(signature_to_der(r, s)), der);
+            assert_eq!(signature_from_der(&hex::decode(der).unwrap()), Some((r, s)));
+        }
+    }
+
+    #[test]
+    fn correct_signature_eth_bytes() {
+        let sk = u256_from_hex_string(
+            "c28a9f80738f770d527803a566cf6fc3edf6cea586c4fc4a5223a5ad797e1ac3",
+        );
+        let k = u256_from_hex_string(
+            "6f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8",
+        );
+        let message = u256_from_hex_string(
+            "4b688df40bcedbe641ddb16ff0a1842d9c67ea1c3bf63f3e0471baa664531d1a",
+        );
+        let expected = "3f3290969876e55f53c95ab9ffe544a5672f1ea679d7478dd790970b3b81d6b9ac1c5feaa19264809fb3df290e226c3103e629bfae6de65b8c938199574cb0351b";
+
+        let (r, s) = ecdsa_sign_native(sk, k, message, &secp256k1::prelude::PARAMS);
+        let bytes = signature_to_eth_bytes(r, s, 0);
+        assert_eq!(hex::encode(bytes), expected);
+        assert_eq!(signature_from_eth_bytes(&bytes), Some((r, s, 0)));
+
+        // the parsed signature recovers the signer
+        let (r, s, v) = signature_from_eth_bytes(&bytes).unwrap();
+        assert_eq!(
+            ecdsa_recover_native((r, s), v, message, &secp256k1::prelude::PARAMS),
+            Some(derive_public_key_native(sk, &secp256k1::prelude::PARAMS))
+        );
+
+        let bytes = signature_to_eth_bytes(U256::ONE, U256::from(2u8), 1);
+        assert_eq!(bytes[31], 1);
+        assert_eq!(bytes[63], 2);
+        assert_eq!(bytes[64], 28);
+        assert_eq!(
+            signature_from_eth_bytes(&bytes),
+            Some((U256::ONE, U256::from(2u8), 1))
+        );
+        for v in [0, 1, 26, 29] {
+            let mut bytes = bytes;
+            bytes[64] = v;
+            assert_eq!(signature_from_eth_bytes(&bytes), None);
+        }
+    }
+
+    #[test]
+    fn reject_invalid_signature_der() {
+        let invalid = [
+            // zero padded r
+            "30080202000102020080",
+            // negative s
+            "3006020101020180",
+            // trailing byte
+            "300702010102010100",
+            // wrong sequence length
+            "3007020101020101",
+            // wrong integer tag
+            "3006030101020101",
+            "",
+        ];
+        for der in invalid {
+            assert_eq!(signature_from_der(&hex::decode(der).unwrap()), None);
+        }
+    }
+
+    #[test]
+    fn correct_rfc6979_nonce_native() {
+        // sha256("Satoshi Nakamoto") with secret key 1
+        let message = u256_from_decimal_string(
+            "72759466100064397073952777052424474334519735946222029294952053344302920927294",
+        );
+        let k = rfc6979_nonce_native(u256_from_decimal_string("1"), message, *FR_MODULO);
+        assert_eq!(
+            k,
+            u256_from_decimal_string(
+                "64924834324861491611287137376651443740140697549795241003556133838654234778901"
+            )
+        );
+
+        // sha256("Satoshi Nakamoto") with secret key n - 1
+        let k = rfc6979_nonce_native(
+            *FR_MODULO - u256_from_decimal_string("1"),
+            message,
+            *FR_MODULO,
+        );
+        assert_eq!(
+            k,
+            u256_from_decimal_string(
+                "23353490037854490914269644408047295748724802237888307206247513156372757376912"
+            )
+        );
+
+        let k: u8 = rfc6979_nonce_native(77, 89, 109);
+        assert_eq!(k, 10);
+    }
+
+    #[test]
+    fn correct_ecdsa_sign_deterministic_native() {
+        let params = crate::ops::testcurve::PARAMS;
+        let r_modulo = params.n;
+
+        let sk = OsRng.gen_range(1..r_modulo);
+        let message = OsRng.gen_range(1..r_modulo);
+        let pk = derive_public_key_native(sk, &params);
+        let signature = ecdsa_sign_deterministic_native(sk, message, &params);
+        assert_eq!(
+            signature,
+            ecdsa_sign_deterministic_native(sk, message, &params)
+        );
+        let is_valid = ecdsa_verify_native(signature, message, pk, &params);
+        assert!(is_valid, "ECDSA signature is invalid");
+    }
+
+    #[test]
+    fn correct_normalize_s_native() {
+        let params = crate::ops::testcurve::PARAMS;
+        let r_modulo = params.n;
+
+        assert_eq!(normalize_s_native(54, r_modulo), 54);
+        assert_eq!(normalize_s_native(55, r_modulo), 54);
+        assert_eq!(normalize_s_native(108, r_modulo), 1);
+
+        let sk = OsRng.gen_range(1..r_modulo);
+        let k = OsRng.gen_range(1..r_modulo);
+        let message = OsRng.gen_range(1..r_modulo);
+        let pk = derive_public_key_native(sk, &params);
+        let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+        let s = normalize_s_native(s, r_modulo);
+        assert!(s <= r_modulo / 2);
+        let is_valid = ecdsa_verify_native((r, s), message, pk, &params);
+        assert!(is_valid, "ECDSA signature is invalid");
+    }
+
+    #[test]
+    fn reject_zero_ecdsa_signature_native() {
+        let params = crate::ops::testcurve::PARAMS;
+        let (q_modulo, r_modulo) = (params.p, params.n);
+        let (gx, gy) = params.generator;
+
+        let sk = 77;
+        let k = 35;
+        let message = 89;
+        let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
+        let pk = group_projective_into_affine_native(
+            pk_projective.0,
+            pk_projective.1,
+            pk_projective.2,
+            q_modulo,
+        );
+        let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+        assert!(!ecdsa_verify_native((0, s), message, pk, &params));
+        assert!(!ecdsa_verify_native((r, 0), message, pk, &params));
+        assert!(!ecdsa_verify_native((r, r_modulo), message, pk, &params));
+    }
+
+    /// progress is logged at debug and decrypted intermediates at trace,
+    /// neither reaches the logger installed in `init` unless `RUST_LOG` asks for it
+    #[test]
+    fn no_log_output_at_default_level() {
+        if std::env::var("RUST_LOG").is_ok() {
+            return;
+        }
+        assert!(!log_enabled!(target: "ecdsa::ecdsa", Level::Debug));
+        assert!(!log_enabled!(target: "ecdsa::ecdsa", Level::Trace));
+        assert!(log_enabled!(target: "ecdsa::ecdsa", Level::Warn));
+    }
+}
+
+#[test]
+fn correct_ecdsa_sign_verify() {
+    let params = crate::ops::testcurve::PARAMS;
+    let q_modulo = params.p;
+    let (gx, gy) = params.generator;
+
+    let sk = 77;
+    let k = 35;
+    let message = 89;
+    let (rx, ry) = ecdsa_sign_native(sk, k, message, &params);
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+
+    let (enc_rx, enc_ry) =
+        ecdsa_sign::<NUM_BLOCK, _>(&enc_sk, &enc_k, message, &params, &server_key);
+
+    assert_eq!(rx, u8::decrypt(&enc_rx, &client_key));
+    assert_eq!(ry, u8::decrypt(&enc_ry, &client_key));
+
+    let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
+    let pk = group_projective_into_affine_native(
+        pk_projective.0,
+        pk_projective.1,
+        pk_projective.2,
+        q_modulo,
+    );
+    let is_valid = ecdsa_verify_native((rx, ry), message, pk, &params);
+    assert!(is_valid, "ECDSA signature is invalid");
+}
+
+#[test]
+fn correct_ecdsa_sign_verify_random() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (q_modulo, r_modulo) = (params.p, params.n);
+    let (gx, gy) = params.generator;
+
+    let sk = OsRng.gen_range(1..r_modulo);
+    let k = OsRng.gen_range(1..r_modulo);
+    let message = OsRng.gen_range(1..r_modulo);
+    let (rx, ry) = ecdsa_sign_native(sk, k, message, &params);
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+
+    let (enc_rx, enc_ry) =
+        ecdsa_sign::<NUM_BLOCK, _>(&enc_sk, &enc_k, message, &params, &server_key);
+
+    assert_eq!(rx, u8::decrypt(&enc_rx, &client_key));
+    assert_eq!(ry, u8::decrypt(&enc_ry, &client_key));
+
+    let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
+    let pk = group_projective_into_affine_native(
+        pk_projective.0,
+        pk_projective.1,
+        pk_projective.2,
+        q_modulo,
+    );
+    let is_valid = ecdsa_verify_native((rx, ry), message, pk, &params);
+    assert!(is_valid, "ECDSA signature is invalid");
+}
+
+#[test]
+fn correct_ecdsa_verify() {
+    let params = crate::ops::testcurve::PARAMS;
+    let q_modulo = params.p;
+    let (gx, gy) = params.generator;
+
+    let sk = 77;
+    let k = 35;
+    let message = 89;
+    let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+    let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
+    let pk = group_projective_into_affine_native(
+        pk_projective.0,
+        pk_projective.1,
+        pk_projective.2,
+        q_modulo,
+    );
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_r = client_key.encrypt_radix(r, NUM_BLOCK);
+    let enc_s = client_key.encrypt_radix(s, NUM_BLOCK);
+    let enc_pk = (
+        client_key.encrypt_radix(pk.0, NUM_BLOCK),
+        client_key.encrypt_radix(pk.1, NUM_BLOCK),
+    );
+
+    let is_valid = ecdsa_verify::<NUM_BLOCK, _>(
+        (&enc_r, &enc_s),
+        message,
+        (&enc_pk.0, &enc_pk.1),
+        &params,
+        &server_key,
+    );
+    assert_eq!(u8::decrypt(&is_valid, &client_key), 1);
+
+    let is_valid = ecdsa_verify::<NUM_BLOCK, _>(
+        (&enc_r, &enc_s),
+        message + 1,
+        (&enc_pk.0, &enc_pk.1),
+        &params,
+        &server_key,
+    );
+    assert_eq!(u8::decrypt(&is_valid, &client_key), 0);
+}
+
+#[test]
+fn correct_derive_public_key() {
+    let params = crate::ops::testcurve::PARAMS;
+
+    let sk = OsRng.gen_range(1..params.n);
+    let pk = derive_public_key_native(sk, &params);
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let (enc_x, enc_y) = derive_public_key::<NUM_BLOCK, _>(&enc_sk, &params, &server_key);
+
+    assert_eq!(pk.0, u8::decrypt(&enc_x, &client_key));
+    assert_eq!(pk.1, u8::decrypt(&enc_y, &client_key));
+}
+
+#[test]
+fn correct_normalize_s() {
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    let r_modulo: u8 = 109;
+
+    let mut values = vec![1, 53, 54, 55, 56, 108];
+    values.extend((0..4).map(|_| OsRng.gen_range(45..65)));
+    for s in values {
+        let enc_s = client_key.encrypt_radix(s, NUM_BLOCK);
+        let enc_normalized = normalize_s::<NUM_BLOCK, _>(&enc_s, r_modulo, &server_key);
+        assert_eq!(
+            normalize_s_native(s, r_modulo),
+            u8::decrypt(&enc_normalized, &client_key)
+        );
+    }
+}
+
+#[test]
+fn correct_ecdsa_sign_batch() {
+    let params = crate::ops::testcurve::PARAMS;
+    let r_modulo = params.n;
+
+    let sk = OsRng.gen_range(1..r_modulo);
+    let k = [OsRng.gen_range(1..r_modulo), OsRng.gen_range(1..r_modulo)];
+    let messages = [OsRng.gen_range(1..r_modulo), OsRng.gen_range(1..r_modulo)];
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = k
+        .iter()
+        .map(|k| client_key.encrypt_radix(*k, NUM_BLOCK))
+        .collect::<Vec<_>>();
+
+    let signatures =
+        ecdsa_sign_batch::<NUM_BLOCK, _>(&enc_sk, &enc_k, &messages, &params, &server_key);
+
+    for ((k, message), (enc_r, enc_s)) in k.iter().zip(messages.iter()).zip(signatures.iter()) {
+        let (r, s) = ecdsa_sign_native(sk, *k, *message, &params);
+        assert_eq!(r, u8::decrypt(enc_r, &client_key));
+        assert_eq!(s, u8::decrypt(enc_s, &client_key));
+    }
+}
+
+#[test]
+fn correct_ecdsa_sign_iter() {
+    let params = crate::ops::testcurve::PARAMS;
+    let r_modulo = params.n;
+
+    let sk = OsRng.gen_range(1..r_modulo);
+    let k = [OsRng.gen_range(1..r_modulo), OsRng.gen_range(1..r_modulo)];
+    let messages = [OsRng.gen_range(1..r_modulo), OsRng.gen_range(1..r_modulo)];
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = k
+        .iter()
+        .map(|k| client_key.encrypt_radix(*k, NUM_BLOCK))
+        .collect::<Vec<_>>();
+
+    let batch = ecdsa_sign_batch::<NUM_BLOCK, _>(&enc_sk, &enc_k, &messages, &params, &server_key);
+    let signatures = ecdsa_sign_iter::<NUM_BLOCK, _, _>(
+        &enc_sk,
+        enc_k.iter().cloned().zip(messages),
+        &params,
+        &server_key,
+    );
+
+    for ((enc_r, enc_s), (batch_r, batch_s)) in signatures.zip(batch.iter()) {
+        assert_eq!(
+            u8::decrypt(&enc_r, &client_key),
+            u8::decrypt(batch_r, &client_key)
+        );
+        assert_eq!(
+            u8::decrypt(&enc_s, &client_key),
+            u8::decrypt(batch_s, &client_key)
+        );
+    }
+
+    // nothing is signed until the iterator is pulled
+    let mut signatures = ecdsa_sign_iter::<NUM_BLOCK, _, _>(
+        &enc_sk,
+        enc_k.iter().cloned().zip(messages),
+        &params,
+        &server_key,
+    );
+    let (enc_r, enc_s) = signatures.next().unwrap();
+    let (r, s) = ecdsa_sign_native(sk, k[0], messages[0], &params);
+    assert_eq!(r, u8::decrypt(&enc_r, &client_key));
+    assert_eq!(s, u8::decrypt(&enc_s, &client_key));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "k has 8 blocks, expected NB = 4")]
+fn reject_ecdsa_sign_wrong_block_count() {
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    let params = crate::ops::testcurve::PARAMS;
+
+    let enc_sk = client_key.encrypt_radix(77u8, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(35u8, NUM_BLOCK * 2);
+    ecdsa_sign::<NUM_BLOCK, _>(&enc_sk, &enc_k, 89, &params, &server_key);
+}
+
+#[test]
+fn correct_ecdsa_sign_private_msg() {
+    let params = crate::ops::testcurve::PARAMS;
+    let r_modulo = params.n;
+
+    let sk = OsRng.gen_range(1..r_modulo);
+    let k = OsRng.gen_range(1..r_modulo);
+    let message = OsRng.gen_range(1..r_modulo);
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+    let enc_message = client_key.encrypt_radix(message, NUM_BLOCK);
+
+    let (enc_r, enc_s) =
+        ecdsa_sign_private_msg::<NUM_BLOCK, _>(&enc_sk, &enc_k, &enc_message, &params, &server_key);
+    let (enc_r_plain, enc_s_plain) =
+        ecdsa_sign::<NUM_BLOCK, _>(&enc_sk, &enc_k, message, &params, &server_key);
+
+    let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+    assert_eq!(r, u8::decrypt(&enc_r, &client_key));
+    assert_eq!(s, u8::decrypt(&enc_s, &client_key));
+    assert_eq!(
+        u8::decrypt(&enc_r_plain, &client_key),
+        u8::decrypt(&enc_r, &client_key)
+    );
+    assert_eq!(
+        u8::decrypt(&enc_s_plain, &client_key),
+        u8::decrypt(&enc_s, &client_key)
+    );
+}
+
+#[test]
+fn correct_ecdsa_sign_with_trace() {
+    let params = crate::ops::testcurve::PARAMS;
+    let r_modulo = params.n;
+
+    let sk = OsRng.gen_range(1..r_modulo);
+    let k = OsRng.gen_range(1..r_modulo);
+    let message = OsRng.gen_range(1..r_modulo);
+
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+
+    let ((enc_r, enc_s), trace) =
+        ecdsa_sign_with_trace::<NUM_BLOCK, _>(&enc_sk, &enc_k, message, &params, &server_key);
+
+    let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+    assert_eq!(r, u8::decrypt(&enc_r, &client_key));
+    assert_eq!(s, u8::decrypt(&enc_s, &client_key));
+    let r_sk = mul_mod_native(r, sk, r_modulo);
+    assert_eq!(
+        inverse_mod_native(k, r_modulo),
+        u8::decrypt(&trace.k_inv, &client_key)
+    );
+    assert_eq!(r_sk, u8::decrypt(&trace.r_sk, &client_key));
+    assert_eq!(
+        add_mod_native(message, r_sk, r_modulo),
+        u8::decrypt(&trace.m_r_sk, &client_key)
+    );
+}
+
+#[test]
+fn correct_signature_serialization() {
+    let params = crate::ops::testcurve::PARAMS;
+    let r_modulo = params.n;
 
     let sk = OsRng.gen_range(1..r_modulo);
     let k = OsRng.gen_range(1..r_modulo);
     let message = OsRng.gen_range(1..r_modulo);
-    let (rx, ry) = ecdsa_sign_native(sk, k, message, (gx, gy), q_modulo, r_modulo);
+    let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+
+    let (client_key, _server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    let signature = (
+        client_key.encrypt_radix(r, NUM_BLOCK),
+        client_key.encrypt_radix(s, NUM_BLOCK),
+    );
+    let bytes = serialize_signature(&signature);
+    let (enc_r, enc_s) = deserialize_signature(&bytes).unwrap();
+
+    assert_eq!(r, u8::decrypt(&enc_r, &client_key));
+    assert_eq!(s, u8::decrypt(&enc_s, &client_key));
+    assert!(deserialize_signature(&bytes[..bytes.len() / 2]).is_err());
+}
+
+#[test]
+fn correct_ecdsa_signer() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    let (sk, k, message) = (77, 35, 89);
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+    let decrypt = |(r, s): (RadixCiphertext, RadixCiphertext)| {
+        (u8::decrypt(&r, &client_key), u8::decrypt(&s, &client_key))
+    };
+
+    // defaults sign like the free function
+    let signer = EcdsaSigner::<NUM_BLOCK, _>::new(&params, &server_key);
+    let signature = decrypt(signer.sign(&enc_sk, &enc_k, message));
+    assert_eq!(
+        signature,
+        decrypt(ecdsa_sign::<NUM_BLOCK, _>(
+            &enc_sk,
+            &enc_k,
+            message,
+            &params,
+            &server_key
+        ))
+    );
+    assert_eq!(signature, ecdsa_sign_native(sk, k, message, &params));
+
+    let signer = EcdsaSigner::<NUM_BLOCK, _>::new(&params, &server_key)
+        .low_s(true)
+        .reduction(ReductionStrategy::Barrett);
+    // s = 104 > n / 2
+    let message = 87;
+    let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+    assert_eq!(s, 104);
+    assert_eq!(
+        decrypt(signer.sign(&enc_sk, &enc_k, message)),
+        (r, normalize_s_native(s, params.n))
+    );
+}
 
+#[test]
+fn correct_ecdsa_sign_single_thread_pool() {
+    let params = crate::ops::testcurve::PARAMS;
     let (client_key, server_key) =
         IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
     const NUM_BLOCK: usize = 4;
+    let enc_sk = client_key.encrypt_radix(77u8, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(35u8, NUM_BLOCK);
+
+    // a scoped pool instead of `set_thread_pool`, the global pool is shared with the other tests
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let (enc_r, enc_s) = pool.install(|| {
+        assert_eq!(install_thread_pool(rayon::current_num_threads), 1);
+        ecdsa_sign::<NUM_BLOCK, _>(&enc_sk, &enc_k, 89, &params, &server_key)
+    });
+
+    assert_eq!(
+        (
+            u8::decrypt(&enc_r, &client_key),
+            u8::decrypt(&enc_s, &client_key)
+        ),
+        (15, 48)
+    );
+}
+
+#[test]
+#[should_panic(expected = "homomorphic doubling requires a curve with a = 0")]
+fn reject_ecdsa_signer_secp256r1() {
+    let (_, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    EcdsaSigner::<128, U256>::new(&crate::ops::secp256r1::prelude::PARAMS, &server_key);
+}
 
+#[test]
+fn correct_resume_sign() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    let (sk, k, message) = (77u8, 35u8, 89);
     let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
     let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+    let decrypt = |(r, s): (RadixCiphertext, RadixCiphertext)| {
+        (u8::decrypt(&r, &client_key), u8::decrypt(&s, &client_key))
+    };
 
-    let (enc_rx, enc_ry) = ecdsa_sign::<NUM_BLOCK, _>(
+    let mut saved = vec![];
+    let signature = ecdsa_sign_resumable::<NUM_BLOCK, _, _>(
         &enc_sk,
         &enc_k,
         message,
-        (gx, gy),
-        q_modulo,
-        r_modulo,
+        &params,
         &server_key,
+        |state| saved.push(state.save()),
     );
+    assert_eq!(decrypt(signature), (15, 48));
+    // 8 bits in windows of WINDOW bits
+    assert_eq!(saved.len(), (8 + WINDOW - 1) / WINDOW);
 
-    assert_eq!(rx, u8::decrypt(&enc_rx, &client_key));
-    assert_eq!(ry, u8::decrypt(&enc_ry, &client_key));
+    // resume after the first window, as if the run stopped there
+    let state = SignState::load(&saved[0]).unwrap();
+    assert_eq!(state.bit, WINDOW);
+    assert_eq!(u8::decrypt(&state.scalar, &client_key), k >> WINDOW);
+    let mut resumed_steps = 0;
+    let resumed = resume_sign::<NUM_BLOCK, _, _>(
+        &saved[0],
+        &enc_sk,
+        &enc_k,
+        message,
+        &params,
+        &server_key,
+        |_| resumed_steps += 1,
+    )
+    .unwrap();
+    assert_eq!(resumed_steps, saved.len() - 1);
+    assert_eq!(decrypt(resumed), (15, 48));
 
-    let pk_projective = group_projective_scalar_mul_native(gx, gy, sk, q_modulo);
-    let pk = group_projective_into_affine_native(
-        pk_projective.0,
-        pk_projective.1,
-        pk_projective.2,
-        q_modulo,
+    assert!(resume_sign::<NUM_BLOCK, _, _>(
+        &saved[0][..10],
+        &enc_sk,
+        &enc_k,
+        message,
+        &params,
+        &server_key,
+        |_| {}
+    )
+    .is_err());
+}
+
+#[test]
+fn correct_compute_recovery_bits() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    // R = k * G with recovery id 1, 0, 2 and 3
+    for k in [2u8, 1, 16, 35] {
+        let (rx, ry) = scalar_mul_native(k, params.generator, &params);
+        let recid = compute_recovery_bits::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(ry, NUM_BLOCK),
+            &client_key.encrypt_radix(rx, NUM_BLOCK),
+            params.n,
+            &server_key,
+        );
+        let expected = recovery_id_native((rx, ry), params.n);
+        assert_eq!(u8::decrypt(&recid, &client_key), expected);
+
+        let message = 89;
+        let signature = ecdsa_sign_native(77, k, message, &params);
+        assert_eq!(
+            ecdsa_recover_native(signature, expected, message, &params),
+            Some(derive_public_key_native(77, &params))
+        );
+    }
+}
+
+#[test]
+fn correct_ecdsa_sign_half() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    let (sk, k, message) = (77u8, 35u8, 89);
+
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+
+    // the server returns R encrypted, the key holder decrypts it and sends back r
+    let (enc_x, enc_y) = ecdsa_sign_half_point::<NUM_BLOCK, _>(&enc_k, &params, &server_key);
+    let r_point = AffinePoint::new(
+        u8::decrypt(&enc_x, &client_key),
+        u8::decrypt(&enc_y, &client_key),
     );
-    let is_valid = ecdsa_verify_native((rx, ry), message, pk, (gx, gy), q_modulo, r_modulo);
-    assert!(is_valid, "ECDSA signature is invalid");
+    assert_eq!(
+        r_point,
+        AffinePoint::from(scalar_mul_native(k, params.generator, &params))
+    );
+    let r = r_point.x() % params.n;
+
+    let enc_s = ecdsa_sign_half::<NUM_BLOCK, _>(&enc_sk, &enc_k, r, message, &params, &server_key);
+    let signature = (r, u8::decrypt(&enc_s, &client_key));
+    assert_eq!(signature, (15, 48));
+    let pk = derive_public_key_native(sk, &params);
+    assert_signature_valid_native(pk, message, signature.0, signature.1, &params);
+}
+
+#[test]
+fn correct_x_mod_n() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+
+    // q = 127 < 2n = 218, every affine x < q takes the single subtraction of `modulo_fast`
+    for x in [0u8, 1, 108, 109, 110, 126] {
+        let enc_r = x_mod_n::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(x, NUM_BLOCK),
+            params.p,
+            params.n,
+            &server_key,
+        );
+        assert_eq!(enc_r.blocks().len(), NUM_BLOCK);
+        assert_eq!(u8::decrypt(&enc_r, &client_key), x % params.n, "x {}", x);
+    }
+}
+
+#[test]
+#[should_panic(expected = "homomorphic doubling requires a curve with a = 0")]
+fn reject_compute_r_secp256r1() {
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    let enc_k = client_key.encrypt_radix(35u8, 128);
+    compute_r::<128, U256>(&enc_k, &crate::ops::secp256r1::prelude::PARAMS, &server_key);
+}
+
+#[test]
+fn correct_compute_r() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    let (sk, k, message) = (77u8, 35u8, 89);
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+
+    let enc_r = compute_r::<NUM_BLOCK, _>(&enc_k, &params, &server_key);
+    let (enc_sign_r, _) = ecdsa_sign::<NUM_BLOCK, _>(
+        &client_key.encrypt_radix(sk, NUM_BLOCK),
+        &enc_k,
+        message,
+        &params,
+        &server_key,
+    );
+    assert_eq!(u8::decrypt(&enc_r, &client_key), 15);
+    assert_eq!(
+        u8::decrypt(&enc_r, &client_key),
+        u8::decrypt(&enc_sign_r, &client_key)
+    );
+}
+
+#[test]
+fn correct_mul_mod_n() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    // 108 * 107 is 2 % n = 109 but 126 % p = 127
+    let (a, b) = (108u8, 107u8);
+    let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+    let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+    for reduction in [None, Some(ReductionStrategy::Barrett)] {
+        let res = mul_mod_n::<NUM_BLOCK, _>(&enc_a, &enc_b, params.n, reduction, &server_key);
+        assert_eq!(u8::decrypt(&res, &client_key), 2);
+        assert_ne!(mul_mod_native(a, b, params.p), 2);
+    }
+
+    // s = k^-1 * (m + r * sk) of the signature (15, 48) from encrypted k^-1, r and sk
+    let (sk, k, message) = (77u8, 35u8, 89u8);
+    let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+    assert_eq!((r, s), (15, 48));
+    let enc_k_inv = client_key.encrypt_radix(inverse_mod_native(k, params.n), NUM_BLOCK);
+    let enc_r = client_key.encrypt_radix(r, NUM_BLOCK);
+    let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+    let enc_message = client_key.encrypt_radix(message, NUM_BLOCK);
+    let rsk = mul_mod_n::<NUM_BLOCK, _>(&enc_r, &enc_sk, params.n, None, &server_key);
+    let mrsk = add_mod::<NUM_BLOCK, _>(&enc_message, &rsk, params.n, &server_key);
+    let enc_s = mul_mod_n::<NUM_BLOCK, _>(&enc_k_inv, &mrsk, params.n, None, &server_key);
+    assert_eq!(u8::decrypt(&enc_s, &client_key), s);
+}
+
+#[test]
+fn correct_ecdsa_sign_r_x_above_n() {
+    let params = crate::ops::testcurve::PARAMS;
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    const NUM_BLOCK: usize = 4;
+    // R = 16G = (111, 88) with n <= R.x < p, so r = R.x - n = 2 and recovery id 2
+    let (sk, k, message) = (77u8, 16u8, 89);
+    let r_point = scalar_mul_native(k, params.generator, &params);
+    assert_eq!(r_point, (111, 88));
+    let signature = ecdsa_sign_native(sk, k, message, &params);
+    assert_eq!(signature, (2, 22));
+    let recovery_id = recovery_id_native(r_point, params.n);
+    assert_eq!(recovery_id, 2);
+    assert_eq!(
+        ecdsa_recover_native(signature, recovery_id, message, &params),
+        Some(derive_public_key_native(sk, &params))
+    );
+
+    let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+    let (enc_r, enc_s) = ecdsa_sign::<NUM_BLOCK, _>(
+        &client_key.encrypt_radix(sk, NUM_BLOCK),
+        &enc_k,
+        message,
+        &params,
+        &server_key,
+    );
+    assert_eq!(
+        (
+            u8::decrypt(&enc_r, &client_key),
+            u8::decrypt(&enc_s, &client_key)
+        ),
+        signature
+    );
+
+    let table = generator_table(&params);
+    let (x, y, z) = group_projective_scalar_mul_fixed_window::<WINDOW, NUM_BLOCK, _>(
+        &enc_k,
+        &table,
+        params.p,
+        &server_key,
+    );
+    let (x, y) = group_projective_into_affine::<NUM_BLOCK, _>(&x, &y, &z, params.p, &server_key);
+    let enc_recovery_id = compute_recovery_bits::<NUM_BLOCK, _>(&y, &x, params.n, &server_key);
+    assert_eq!(u8::decrypt(&enc_recovery_id, &client_key), recovery_id);
 }
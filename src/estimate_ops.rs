@@ -0,0 +1,216 @@
+use std::ops::{Add, Mul};
+
+use crate::WINDOW;
+
+/// predicted number of homomorphic modular operations, counted the same way as `ProtocolLowOps`
+/// so it can be compared against `ProtocolStats` without running any FHE
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CostEstimate {
+    pub add_mod: usize,
+    pub sub_mod: usize,
+    pub double_mod: usize,
+    pub mul_mod: usize,
+    pub square_mod: usize,
+    pub inverse_mod: usize,
+}
+
+impl CostEstimate {
+    /// `mul_mod` and `square_mod`
+    pub fn multiplies(&self) -> usize {
+        self.mul_mod + self.square_mod
+    }
+
+    /// `add_mod` and `sub_mod`
+    pub fn additions(&self) -> usize {
+        self.add_mod + self.sub_mod
+    }
+
+    /// `double_mod` is a left shift by 1 followed by a reduction
+    pub fn shifts(&self) -> usize {
+        self.double_mod
+    }
+}
+
+impl Add for CostEstimate {
+    type Output = CostEstimate;
+
+    fn add(self, rhs: CostEstimate) -> CostEstimate {
+        CostEstimate {
+            add_mod: self.add_mod + rhs.add_mod,
+            sub_mod: self.sub_mod + rhs.sub_mod,
+            double_mod: self.double_mod + rhs.double_mod,
+            mul_mod: self.mul_mod + rhs.mul_mod,
+            square_mod: self.square_mod + rhs.square_mod,
+            inverse_mod: self.inverse_mod + rhs.inverse_mod,
+        }
+    }
+}
+
+impl Mul<usize> for CostEstimate {
+    type Output = CostEstimate;
+
+    fn mul(self, rhs: usize) -> CostEstimate {
+        CostEstimate {
+            add_mod: self.add_mod * rhs,
+            sub_mod: self.sub_mod * rhs,
+            double_mod: self.double_mod * rhs,
+            mul_mod: self.mul_mod * rhs,
+            square_mod: self.square_mod * rhs,
+            inverse_mod: self.inverse_mod * rhs,
+        }
+    }
+}
+
+/// `group_projective_double`
+pub fn group_projective_double_cost() -> CostEstimate {
+    CostEstimate {
+        add_mod: 3,
+        sub_mod: 4,
+        double_mod: 7,
+        mul_mod: 2,
+        square_mod: 5,
+        inverse_mod: 0,
+    }
+}
+
+/// `group_projective_add_affine`
+pub fn group_projective_add_affine_cost() -> CostEstimate {
+    CostEstimate {
+        add_mod: 0,
+        sub_mod: 6,
+        double_mod: 6,
+        mul_mod: 8,
+        square_mod: 3,
+        inverse_mod: 0,
+    }
+}
+
+/// `group_projective_add_affine_complete`, the doubling for the P == Q case is always computed
+pub fn group_projective_add_affine_complete_cost() -> CostEstimate {
+    group_projective_add_affine_cost() + group_projective_double_cost()
+}
+
+/// `group_projective_add_projective`
+pub fn group_projective_add_projective_cost() -> CostEstimate {
+    CostEstimate {
+        add_mod: 1,
+        sub_mod: 8,
+        double_mod: 4,
+        mul_mod: 11,
+        square_mod: 5,
+        inverse_mod: 0,
+    }
+}
+
+/// `group_projective_add_projective_complete`, the doubling for the P == Q case is always
+/// computed
+pub fn group_projective_add_projective_complete_cost() -> CostEstimate {
+    group_projective_add_projective_cost() + group_projective_double_cost()
+}
+
+/// `group_projective_into_affine_inv` with z^-1 computed outside
+pub fn group_projective_into_affine_inv_cost() -> CostEstimate {
+    CostEstimate {
+        mul_mod: 3,
+        square_mod: 1,
+        ..Default::default()
+    }
+}
+
+/// bit serial scalar mul of a constant point `group_projective_scalar_mul_constant_bits`,
+/// one mixed addition per bit
+pub fn scalar_mul_bit_serial_cost(bits: usize) -> CostEstimate {
+    group_projective_add_affine_cost() * bits
+}
+
+/// windowed scalar mul over a precomputed table `group_projective_scalar_mul_fixed_window`,
+/// one mixed addition per window of `window` bits
+pub fn scalar_mul_fixed_window_cost(bits: usize, window: usize) -> CostEstimate {
+    group_projective_add_affine_cost() * bits.div_ceil(window)
+}
+
+/// `ecdsa_sign` over a `num_block` blocks modulus with 2 message bits per block
+/// e.g. `PARAM_MESSAGE_2_CARRY_2`. assumes r = x mod n is reduced without `mul_mod`.
+pub fn ecdsa_sign_cost(num_block: usize) -> CostEstimate {
+    let bits = num_block * 2;
+    // (x, y) = k * G
+    let scalar_mul = scalar_mul_fixed_window_cost(bits, WINDOW);
+    // z^-1 and k^-1
+    let inverse = CostEstimate {
+        inverse_mod: 2,
+        ..Default::default()
+    };
+    // s = k^-1 * (m + r * sk)
+    let s = CostEstimate {
+        add_mod: 1,
+        mul_mod: 2,
+        ..Default::default()
+    };
+
+    scalar_mul + inverse + group_projective_into_affine_inv_cost() + s
+}
+
+#[cfg(test)]
+mod tests {
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use crate::{
+        ecdsa::ecdsa_sign,
+        ops::testcurve::PARAMS,
+        stats::{ProtocolLowOps, ProtocolStats},
+    };
+
+    use super::{
+        ecdsa_sign_cost, scalar_mul_bit_serial_cost, scalar_mul_fixed_window_cost, CostEstimate,
+    };
+
+    #[test]
+    fn correct_ecdsa_sign_cost() {
+        let cost = ecdsa_sign_cost(128);
+        assert_eq!(cost.multiplies(), 43 * 18 + 6);
+        assert_eq!(cost.additions(), 13 * 43 + 1);
+        assert_eq!(cost.shifts(), 13 * 43);
+
+        // a window of 1 bit is bit serial, a window of W bits needs about W times fewer additions
+        assert_eq!(
+            scalar_mul_bit_serial_cost(256),
+            scalar_mul_fixed_window_cost(256, 1)
+        );
+        assert!(
+            scalar_mul_fixed_window_cost(256, 6).multiplies() * 5
+                < scalar_mul_bit_serial_cost(256).multiplies()
+        );
+    }
+
+    #[test]
+    fn correct_ecdsa_sign_cost_matches_stats() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        // 8 bits in 2 windows of 6 bits
+        const NUM_BLOCK: usize = 4;
+        let sk = client_key.encrypt_radix(5u8, NUM_BLOCK);
+        let k = client_key.encrypt_radix(7u8, NUM_BLOCK);
+
+        let (_, stats) = ProtocolStats::collect(4, || {
+            ecdsa_sign::<NUM_BLOCK, _>(&sk, &k, 10, &PARAMS, &server_key)
+        });
+        let count = |op: ProtocolLowOps| stats.time.get(&op).map_or(0, |t| t.0);
+
+        // inverse mod is not recorded in the stats
+        let estimate = ecdsa_sign_cost(NUM_BLOCK);
+        assert_eq!(
+            CostEstimate {
+                add_mod: count(ProtocolLowOps::AddMod),
+                sub_mod: count(ProtocolLowOps::SubMod),
+                double_mod: count(ProtocolLowOps::DoubleMod),
+                mul_mod: count(ProtocolLowOps::MulMod),
+                square_mod: count(ProtocolLowOps::SquareMod),
+                inverse_mod: estimate.inverse_mod,
+            },
+            estimate
+        );
+    }
+}
@@ -1,41 +1,87 @@
-use num_bigint::BigInt;
-use tfhe::integer::{
-    block_decomposition::{BlockDecomposer, DecomposableInto, RecomposableFrom},
-    ClientKey, U256,
+use std::{
+    fmt::Display,
+    sync::{Arc, RwLock},
 };
 
-use crate::CLIENT_KEY;
-
-pub fn bigint_ilog2_ceil(value: &BigInt) -> u32 {
-    let mut value = value.clone();
-    let mut i = 0;
-    if value == BigInt::from(0) {
-        0
-    } else if value == BigInt::from(1) {
-        1
-    } else {
-        while value > BigInt::from(0) {
-            value >>= 1;
-            i += 1;
-        }
-        i
+use num_bigint::{BigInt, Sign};
+use rayon::ThreadPoolBuilder;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use tfhe::{
+    core_crypto::prelude::Numeric,
+    integer::{
+        block_decomposition::{BlockDecomposer, DecomposableInto, RecomposableFrom},
+        keycache::IntegerKeyCache,
+        ClientKey, IntegerCiphertext, IntegerKeyKind, RadixCiphertext, ServerKey, U256,
+    },
+    shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+};
+
+use crate::{ops::secp256k1::prelude::FR_MODULO, CLIENT_KEY, THREAD_POOL};
+
+/// ceil(log2(value)), `None` for zero and negative values.
+/// powers of two are exact, e.g. 1 -> 0 and 4 -> 2, use `BigInt::bits` for the bit length
+pub fn bigint_ilog2_ceil(value: &BigInt) -> Option<u32> {
+    let floor = bigint_ilog2_floor(value)?;
+    match value.trailing_zeros() == Some(floor as u64) {
+        true => Some(floor),
+        false => Some(floor + 1),
+    }
+}
+
+/// floor(log2(value)), `None` for zero and negative values
+pub fn bigint_ilog2_floor(value: &BigInt) -> Option<u32> {
+    match value.sign() {
+        Sign::Plus => Some(value.bits() as u32 - 1),
+        Sign::Minus | Sign::NoSign => None,
     }
 }
 
-pub fn bigint_ilog2_floor(value: &BigInt) -> u32 {
-    let mut value = value.clone();
-    let mut i = 0;
-    if value == BigInt::from(0) {
-        0
-    } else if value == BigInt::from(1) {
-        1
-    } else {
-        while value > BigInt::from(1) {
-            value >>= 1;
-            i += 1;
+/// minimum number of blocks of `bits_per_block` bits holding any value % `p`, i.e. up to p - 1.
+/// e.g. 128 for the secp256k1 field and group order with `PARAM_MESSAGE_2_CARRY_2`
+pub fn required_blocks(p: &BigInt, bits_per_block: usize) -> usize {
+    assert!(*p > BigInt::from(1), "modulus must be at least 2");
+    assert!(bits_per_block > 0, "blocks need at least 1 bit");
+    let bits = (p - 1).bits() as usize;
+    (bits + bits_per_block - 1) / bits_per_block
+}
+
+/// mu = floor(2^(2k) / p) where k is the bit length of p, used in Barrett reduction
+pub fn barrett_mu(p: &BigInt) -> BigInt {
+    let k = p.bits();
+    (BigInt::from(1) << (2 * k)) / p
+}
+
+/// (R, n') for Montgomery multiplication where R = 2^(num_block * bits_per_block) and
+/// n' = -n^-1 mod R, e.g. `bits_per_block` 2 for `PARAM_MESSAGE_2_CARRY_2`
+pub fn montgomery_params(n: &BigInt, num_block: usize, bits_per_block: usize) -> (BigInt, BigInt) {
+    let r = BigInt::from(1) << (num_block * bits_per_block);
+    // n^-1 mod R by Newton iteration, R is a power of 2 and n is odd
+    let mut n_inv = BigInt::from(1);
+    for _ in 0..r.bits() {
+        n_inv = (&n_inv * (BigInt::from(2) - n * &n_inv)) % &r;
+    }
+    let n_prime = (&r - ((n_inv % &r) + &r) % &r) % &r;
+
+    (r, n_prime)
+}
+
+/// non-adjacent form of `a`, least significant digit first. digits are in {-1, 0, 1}
+/// and no two consecutive digits are non-zero.
+pub fn to_naf<T: DecomposableInto<u8>>(a: T) -> Vec<i8> {
+    let mut value = to_bigint(a);
+    let mut naf = vec![];
+    while value > BigInt::from(0) {
+        if value.bit(0) {
+            let digit = if value.bit(1) { -1 } else { 1 };
+            value -= digit;
+            naf.push(digit as i8);
+        } else {
+            naf.push(0);
         }
-        i
+        value >>= 1;
     }
+    naf
 }
 
 pub fn format<T: DecomposableInto<u8> + Copy>(a: T) -> String {
@@ -48,6 +94,29 @@ pub fn format<T: DecomposableInto<u8> + Copy>(a: T) -> String {
     .to_string()
 }
 
+/// decimal like `format` with `sep` between groups of 3 digits e.g. 1_234_567, for logs only
+pub fn format_grouped<T: DecomposableInto<u8> + Copy>(a: T, sep: char) -> String {
+    let digits = format(a);
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(sep);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// lowercase `0x` prefixed hex with a fixed width of the type e.g. 64 digits for U256
+pub fn format_hex<T: DecomposableInto<u8> + Numeric>(a: T) -> String {
+    let mut bytes = BlockDecomposer::new(a, 8)
+        .iter_as::<u8>()
+        .collect::<Vec<_>>();
+    bytes.resize(<T as Numeric>::BITS / 8, 0);
+    bytes.reverse();
+    format!("0x{}", hex::encode(bytes))
+}
+
 pub fn to_bigint<T: DecomposableInto<u8>>(a: T) -> BigInt {
     BigInt::from_bytes_le(
         num_bigint::Sign::Plus,
@@ -57,7 +126,39 @@ pub fn to_bigint<T: DecomposableInto<u8>>(a: T) -> BigInt {
     )
 }
 
-pub fn from_bigint<T: DecomposableInto<u8> + RecomposableFrom<u8>>(a: &BigInt) -> T {
+/// `from_bigint` input that doesn't fit the target type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionError {
+    Negative,
+    Overflow,
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionError::Negative => f.write_str("negative value for an unsigned type"),
+            ConversionError::Overflow => f.write_str("value exceeds the capacity of the type"),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// `a` as `T`, fails if `a` is negative or has more bits than `T`
+pub fn from_bigint<T: DecomposableInto<u8> + RecomposableFrom<u8>>(
+    a: &BigInt,
+) -> Result<T, ConversionError> {
+    if a.sign() == Sign::Minus {
+        return Err(ConversionError::Negative);
+    }
+    if a.bits() > (std::mem::size_of::<T>() * 8) as u64 {
+        return Err(ConversionError::Overflow);
+    }
+    Ok(from_bigint_unchecked(a))
+}
+
+/// `a` as `T` without bounds check, `a` must be non negative and fit `T`
+pub fn from_bigint_unchecked<T: DecomposableInto<u8> + RecomposableFrom<u8>>(a: &BigInt) -> T {
     let mut res = T::ZERO;
     for (i, b) in a.to_bytes_le().1.iter().enumerate() {
         res += T::cast_from(*b) << (i * 8) as u32;
@@ -82,12 +183,11 @@ pub fn bigint_to_u256(a: &BigInt) -> U256 {
     res
 }
 
+/// `a` as u128, panics if `a` is negative or has more than 128 bits instead of dropping the
+/// high bytes, e.g. the c = 2^n - p of a prime that does not fit the u128 scalar mul.
+/// `from_bigint::<u128>` is the non panicking version.
 pub fn bigint_to_u128(a: &BigInt) -> u128 {
-    let mut res = 0;
-    for (i, b) in a.to_bytes_le().1.iter().enumerate() {
-        res += (*b as u128) << (i * 8);
-    }
-    res
+    from_bigint::<u128>(a).unwrap_or_else(|err| panic!("{} does not fit u128: {}", a, err))
 }
 
 pub fn u256_from_decimal_string(s: &str) -> U256 {
@@ -99,6 +199,121 @@ pub fn u256_from_decimal_string(s: &str) -> U256 {
     res
 }
 
+pub fn u256_from_hex_string(s: &str) -> U256 {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    bigint_to_u256(&BigInt::parse_bytes(s.as_bytes(), 16).expect("invalid hex string"))
+}
+
+/// big-endian bytes into U256, shorter slices are left padded with zero
+pub fn u256_from_be_bytes(bytes: &[u8]) -> U256 {
+    assert!(bytes.len() <= 32, "U256 holds at most 32 bytes");
+    let mut res = U256::ZERO;
+    for b in bytes {
+        res <<= 8u32;
+        res += U256::from(*b);
+    }
+    res
+}
+
+pub fn u256_to_be_bytes(a: U256) -> [u8; 32] {
+    let mut res = [0u8; 32];
+    for (i, b) in BlockDecomposer::new(a, 8)
+        .iter_as::<u8>()
+        .take(32)
+        .enumerate()
+    {
+        res[31 - i] = b;
+    }
+    res
+}
+
+/// hash of a message to a scalar % `order` to sign, see `ecdsa_sign_hashed`.
+/// keeps the hash function out of the signing so other schemes like BIP340 can reuse it.
+pub trait HashToField {
+    fn hash(&self, msg: &[u8], order: U256) -> U256;
+}
+
+/// SHA-256, the digest Bitcoin signs
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha256Hash;
+
+impl HashToField for Sha256Hash {
+    fn hash(&self, msg: &[u8], order: U256) -> U256 {
+        digest_to_field(&Sha256::digest(msg), order)
+    }
+}
+
+/// keccak-256, the digest Ethereum signs
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Keccak256Hash;
+
+impl HashToField for Keccak256Hash {
+    fn hash(&self, msg: &[u8], order: U256) -> U256 {
+        digest_to_field(&Keccak256::digest(msg), order)
+    }
+}
+
+/// big-endian `digest` truncated to the bit length of `order` like ECDSA's bits2int, then % order
+fn digest_to_field(digest: &[u8], order: U256) -> U256 {
+    let order_bigint = to_bigint(order);
+    let excess = (digest.len() as u64 * 8).saturating_sub(order_bigint.bits());
+    let digest = BigInt::from_bytes_be(Sign::Plus, digest) >> excess;
+    from_bigint_unchecked(&(digest % order_bigint))
+}
+
+/// SHA-256 digest of `msg` as a big-endian U256 message for secp256k1 signing.
+/// the order has 256 bits so the digest is not truncated, it is only reduced % n.
+pub fn hash_message_sha256(msg: &[u8]) -> U256 {
+    Sha256Hash.hash(msg, *FR_MODULO)
+}
+
+/// keccak-256 digest of `msg` as a big-endian U256 message like `hash_message_sha256`,
+/// the digest Ethereum signs, e.g. for `signature_to_eth_bytes`
+pub fn hash_message_keccak256(msg: &[u8]) -> U256 {
+    Keccak256Hash.hash(msg, *FR_MODULO)
+}
+
+/// number of message bits per block of the radix ciphertext e.g. 2 for `PARAM_MESSAGE_2_CARRY_2`
+pub fn bits_per_block(x: &RadixCiphertext) -> usize {
+    x.blocks()[0].message_modulus.0.ilog2() as usize
+}
+
+/// decrypt a radix ciphertext of any width, e.g. a product of 2 * 128 blocks too wide for U256.
+/// decrypts chunks of 256 bits and recombines them.
+pub fn decrypt_bigint_wide(x: &RadixCiphertext, client_key: &ClientKey) -> BigInt {
+    let bits_per_block = bits_per_block(x);
+    let chunk_blocks = 256 / bits_per_block;
+    x.blocks()
+        .chunks(chunk_blocks)
+        .enumerate()
+        .fold(BigInt::from(0), |acc, (i, chunk)| {
+            let chunk = RadixCiphertext::from(chunk.to_vec());
+            let value = to_bigint(client_key.decrypt_radix::<U256>(&chunk));
+            acc + (value << (i * chunk_blocks * bits_per_block))
+        })
+}
+
+/// the global client key was read before `set_client_key` or `setup`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyNotSetError;
+
+impl Display for KeyNotSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("client key is not set, call set_client_key or setup first")
+    }
+}
+
+impl std::error::Error for KeyNotSetError {}
+
+/// clone of the global client key set by `set_client_key` or `setup`
+pub fn client_key() -> Result<ClientKey, KeyNotSetError> {
+    client_key_from(&CLIENT_KEY)
+}
+
+fn client_key_from(key: &RwLock<Option<ClientKey>>) -> Result<ClientKey, KeyNotSetError> {
+    key.read().unwrap().clone().ok_or(KeyNotSetError)
+}
+
 pub fn read_client_key<F: FnOnce(&ClientKey)>(f: F) {
     if let Some(client_key) = CLIENT_KEY.read().unwrap().as_ref() {
         f(client_key);
@@ -109,15 +324,74 @@ pub fn set_client_key(client_key: &ClientKey) {
     *CLIENT_KEY.write().unwrap() = Some(client_key.clone());
 }
 
+/// run the signing operations on a dedicated rayon pool of `num_threads` threads, 0 removes it.
+/// the global rayon pool, sized by `RAYON_NUM_THREADS` or `ThreadPoolBuilder::build_global`,
+/// is left as is and still runs every op called outside of signing. the ops called from a
+/// signing run stay on the dedicated pool since `rayon::join` uses the pool it is called from.
+pub fn set_thread_pool(num_threads: usize) {
+    let pool = match num_threads {
+        0 => None,
+        _ => Some(Arc::new(
+            ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .expect("failed to build the rayon thread pool"),
+        )),
+    };
+    *THREAD_POOL.write().unwrap() = pool;
+}
+
+/// run `f` on the pool of `set_thread_pool`, on the current pool when none is set, e.g. the pool
+/// of a `ThreadPool::install` around the call to bound a single run without the global setting
+pub fn install_thread_pool<R: Send, F: FnOnce() -> R + Send>(f: F) -> R {
+    let pool = THREAD_POOL.read().unwrap().clone();
+    match pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+/// create or load the `PARAM_MESSAGE_2_CARRY_2` radix key pair for ciphertexts of `num_block`
+/// blocks and store the client key for `read_client_key`. the keys don't depend on the block
+/// count, `num_block` only has to match what the caller encrypts with.
+pub fn setup(num_block: usize) -> (ClientKey, ServerKey) {
+    assert!(num_block > 0, "ciphertexts need at least 1 block");
+    let (client_key, server_key) =
+        IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+    set_client_key(&client_key);
+
+    (client_key, server_key)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{str::FromStr, sync::RwLock};
 
     use num_bigint::BigInt;
+    use sha2::{Digest, Sha256};
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind, U256, U512},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
 
-    use crate::helper::{bigint_ilog2_ceil, bigint_ilog2_floor, format};
+    use crate::{
+        helper::{
+            barrett_mu, bigint_ilog2_ceil, bigint_ilog2_floor, bigint_to_u128, decrypt_bigint_wide,
+            format, from_bigint, montgomery_params, required_blocks, to_naf, u256_to_bigint,
+            ConversionError,
+        },
+        numeral::Numeral,
+        ops::{
+            native::modulo_native,
+            secp256k1::prelude::{FQ_MODULO, FR_MODULO},
+        },
+    };
 
-    use super::u256_from_decimal_string;
+    use super::{
+        client_key_from, format_grouped, format_hex, hash_message_keccak256, hash_message_sha256,
+        read_client_key, setup, u256_from_be_bytes, u256_from_decimal_string, u256_from_hex_string,
+        u256_to_be_bytes, HashToField, Keccak256Hash, KeyNotSetError, Sha256Hash,
+    };
 
     #[test]
     fn correct_u256_from_string() {
@@ -131,7 +405,287 @@ mod tests {
         let value = 1237582375u128;
         let bigint_value = BigInt::from(value);
         let res = bigint_ilog2_floor(&bigint_value);
-        assert_eq!(res, value.ilog2());
-        assert_eq!(bigint_ilog2_ceil(&BigInt::from_str("115792089237316195423570985008687907853269984665640564039457584007908834671663").unwrap()), 256);
+        assert_eq!(res, Some(value.ilog2()));
+        assert_eq!(bigint_ilog2_ceil(&BigInt::from_str("115792089237316195423570985008687907853269984665640564039457584007908834671663").unwrap()), Some(256));
+    }
+
+    #[test]
+    fn correct_required_blocks() {
+        assert_eq!(required_blocks(&u256_to_bigint(*FQ_MODULO), 2), 128);
+        assert_eq!(required_blocks(&u256_to_bigint(*FR_MODULO), 2), 128);
+        assert_eq!(required_blocks(&u256_to_bigint(*FQ_MODULO), 4), 64);
+        // the toy curve of the u8 tests
+        assert_eq!(required_blocks(&BigInt::from(199), 2), 4);
+        assert_eq!(required_blocks(&BigInt::from(211), 2), 4);
+        // values % 256 are up to 255, 8 bits
+        assert_eq!(required_blocks(&BigInt::from(256), 2), 4);
+        assert_eq!(required_blocks(&BigInt::from(257), 2), 5);
+        assert_eq!(required_blocks(&BigInt::from(2), 2), 1);
+    }
+
+    #[test]
+    fn correct_bigint_ilog2_edge_cases() {
+        for value in [0, -1, -8] {
+            assert_eq!(bigint_ilog2_floor(&BigInt::from(value)), None);
+            assert_eq!(bigint_ilog2_ceil(&BigInt::from(value)), None);
+        }
+        assert_eq!(bigint_ilog2_floor(&BigInt::from(1)), Some(0));
+        assert_eq!(bigint_ilog2_ceil(&BigInt::from(1)), Some(0));
+        assert_eq!(bigint_ilog2_floor(&BigInt::from(2)), Some(1));
+        assert_eq!(bigint_ilog2_ceil(&BigInt::from(2)), Some(1));
+        assert_eq!(bigint_ilog2_floor(&BigInt::from(3)), Some(1));
+        assert_eq!(bigint_ilog2_ceil(&BigInt::from(3)), Some(2));
+        for value in 1..1024u32 {
+            let bigint_value = BigInt::from(value);
+            assert_eq!(bigint_ilog2_floor(&bigint_value), Some(value.ilog2()));
+            assert_eq!(
+                bigint_ilog2_ceil(&bigint_value),
+                Some(value.next_power_of_two().ilog2())
+            );
+        }
+    }
+
+    #[test]
+    fn correct_barrett_mu() {
+        assert_eq!(barrett_mu(&BigInt::from(251)), BigInt::from(261));
+        assert_eq!(
+            barrett_mu(&BigInt::from_str("115792089237316195423570985008687907852837564279074904382605163141518161494337").unwrap()),
+            BigInt::from_str("115792089237316195423570985008687907853702405052206223696310004874308097785536").unwrap()
+        );
+    }
+
+    #[test]
+    fn correct_montgomery_params() {
+        let (r, n_prime) = montgomery_params(&BigInt::from(199), 4, 2);
+        assert_eq!(r, BigInt::from(256));
+        assert_eq!(n_prime, BigInt::from(9));
+        // 4 blocks of 4 bits
+        let (r, n_prime) = montgomery_params(&BigInt::from(199), 4, 4);
+        assert_eq!(r, BigInt::from(65536));
+        assert_eq!((199 * &n_prime + 1) % &r, BigInt::from(0));
+
+        let n = BigInt::from_str(
+            "115792089237316195423570985008687907852837564279074904382605163141518161494337",
+        )
+        .unwrap();
+        let (r, n_prime) = montgomery_params(&n, 128, 2);
+        assert_eq!((n * n_prime + 1) % &r, BigInt::from(0));
+    }
+
+    #[test]
+    fn correct_to_naf() {
+        assert_eq!(to_naf(7u8), vec![-1, 0, 0, 1]);
+        assert_eq!(to_naf(0u8), vec![]);
+
+        for value in 0..=u16::MAX {
+            let naf = to_naf(value);
+            let recomposed = naf
+                .iter()
+                .rev()
+                .fold(0i64, |acc, digit| acc * 2 + *digit as i64);
+            assert_eq!(recomposed, value as i64);
+            assert!(naf.windows(2).all(|w| w[0] == 0 || w[1] == 0));
+        }
+    }
+
+    #[test]
+    fn correct_u256_hex_string() {
+        let values = [
+            "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "0x00000000000000000000000000000000000000000000000000000000000000ff",
+            "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        ];
+        for value in values {
+            assert_eq!(format_hex(u256_from_hex_string(value)), value);
+        }
+        assert_eq!(
+            u256_from_hex_string(
+                "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+            ),
+            u256_from_decimal_string(
+                "55066263022277343669578718895168534326250603453777594175500187360389116729240"
+            )
+        );
+        assert_eq!(u256_from_hex_string("ff"), u256_from_decimal_string("255"));
+        assert_eq!(format_hex(123u8), "0x7b");
+        assert_eq!(format_hex(1u16), "0x0001");
+    }
+
+    #[test]
+    fn correct_format_grouped() {
+        assert_eq!(format_grouped(0u8, '_'), "0");
+        assert_eq!(format_grouped(199u8, '_'), "199");
+        assert_eq!(format_grouped(1000u16, ','), "1,000");
+        assert_eq!(format_grouped(1234567u32, '_'), "1_234_567");
+        assert_eq!(
+            FR_MODULO.format_grouped('_'),
+            "115_792_089_237_316_195_423_570_985_008_687_907_852_837_564_279_074_904_382_605_163_141_518_161_494_337"
+        );
+        assert_eq!(
+            FR_MODULO.format_grouped('_').replace('_', ""),
+            FR_MODULO.format()
+        );
+    }
+
+    #[test]
+    fn correct_u256_be_bytes() {
+        let digest = Sha256::digest(b"Satoshi Nakamoto");
+        let value = u256_from_be_bytes(&digest);
+        assert_eq!(
+            value,
+            u256_from_decimal_string(
+                "72759466100064397073952777052424474334519735946222029294952053344302920927294"
+            )
+        );
+        assert_eq!(u256_to_be_bytes(value).as_slice(), digest.as_slice());
+
+        assert_eq!(u256_from_be_bytes(&[1, 0]), u256_from_decimal_string("256"));
+        assert_eq!(u256_from_be_bytes(&[]), u256_from_decimal_string("0"));
+        let mut expected = [0u8; 32];
+        expected[30] = 1;
+        assert_eq!(u256_to_be_bytes(u256_from_decimal_string("256")), expected);
+    }
+
+    #[test]
+    fn correct_hash_message_sha256() {
+        // FIPS 180-2 vectors, both below the secp256k1 order
+        assert_eq!(
+            hash_message_sha256(b"abc"),
+            u256_from_hex_string(
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            )
+        );
+        assert_eq!(
+            hash_message_sha256(b""),
+            u256_from_hex_string(
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+            )
+        );
+    }
+
+    #[test]
+    fn correct_hash_message_keccak256() {
+        // original keccak padding, not the NIST SHA3-256 one
+        assert_eq!(
+            hash_message_keccak256(b""),
+            u256_from_hex_string(
+                "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+            )
+        );
+        assert_eq!(
+            hash_message_keccak256(b"abc"),
+            u256_from_hex_string(
+                "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+            )
+        );
+    }
+
+    #[test]
+    fn correct_hash_to_field() {
+        for msg in [&b""[..], b"abc", b"Satoshi Nakamoto", &[0xff; 100]] {
+            let digest = u256_from_be_bytes(&Sha256::digest(msg));
+            assert_eq!(
+                Sha256Hash.hash(msg, *FR_MODULO),
+                modulo_native(digest, *FR_MODULO)
+            );
+            assert_eq!(Sha256Hash.hash(msg, *FR_MODULO), hash_message_sha256(msg));
+            assert_eq!(
+                Keccak256Hash.hash(msg, *FR_MODULO),
+                hash_message_keccak256(msg)
+            );
+        }
+
+        // a 8 bit order keeps the first byte of the digest, 0xba and 0x4e
+        let order = U256::from(199u8);
+        assert_eq!(Sha256Hash.hash(b"abc", order), U256::from(186u8));
+        assert_eq!(Keccak256Hash.hash(b"abc", order), U256::from(78u8));
+        // 0xe3 = 227 is reduced % 199
+        assert_eq!(Sha256Hash.hash(b"", order), U256::from(28u8));
+    }
+
+    #[test]
+    #[should_panic]
+    fn reject_u256_from_long_be_bytes() {
+        u256_from_be_bytes(&[0u8; 33]);
+    }
+
+    #[test]
+    fn correct_setup() {
+        const NUM_BLOCK: usize = 4;
+        let (client_key, server_key) = setup(NUM_BLOCK);
+        let enc =
+            server_key.scalar_add_parallelized(&client_key.encrypt_radix(200u8, NUM_BLOCK), 5);
+        assert_eq!(u8::decrypt(&enc, &client_key), 205);
+
+        let mut decrypted = None;
+        read_client_key(|client_key| decrypted = Some(u8::decrypt(&enc, client_key)));
+        assert_eq!(decrypted, Some(205));
+    }
+
+    #[test]
+    fn reject_unset_client_key() {
+        const NUM_BLOCK: usize = 4;
+        let key = RwLock::new(None);
+        assert_eq!(client_key_from(&key).err(), Some(KeyNotSetError));
+
+        let (client_key, _) = setup(NUM_BLOCK);
+        let enc = client_key.encrypt_radix(123u8, NUM_BLOCK);
+        *key.write().unwrap() = Some(client_key);
+        let client_key = client_key_from(&key).unwrap();
+        assert_eq!(u8::decrypt(&enc, &client_key), 123);
+    }
+
+    #[test]
+    fn correct_decrypt_bigint_wide() {
+        let (client_key, _) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        // a chunk of 128 blocks and a partial one of 72
+        let value = (BigInt::from(1) << 390) - 12345;
+        let enc = client_key.encrypt_radix(from_bigint::<U512>(&value).unwrap(), 200);
+        assert_eq!(decrypt_bigint_wide(&enc, &client_key), value);
+    }
+
+    #[test]
+    fn correct_bigint_to_u128() {
+        assert_eq!(bigint_to_u128(&BigInt::from(977)), 977);
+        assert_eq!(bigint_to_u128(&((BigInt::from(1) << 128) - 1)), u128::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit u128")]
+    fn reject_bigint_to_u128_overflow() {
+        bigint_to_u128(&(BigInt::from(1) << 128));
+    }
+
+    #[test]
+    fn correct_from_bigint() {
+        assert_eq!(from_bigint::<u8>(&BigInt::from(255)), Ok(255));
+        assert_eq!(from_bigint::<u8>(&BigInt::from(0)), Ok(0));
+        assert_eq!(
+            from_bigint::<u8>(&BigInt::from(256)),
+            Err(ConversionError::Overflow)
+        );
+        assert_eq!(
+            from_bigint::<u8>(&BigInt::from(-1)),
+            Err(ConversionError::Negative)
+        );
+
+        let max = (BigInt::from(1) << 256) - 1;
+        assert_eq!(
+            from_bigint::<U256>(&max),
+            Ok(u256_from_hex_string(
+                "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+            ))
+        );
+        assert_eq!(
+            from_bigint::<U256>(&(max + 1)),
+            Err(ConversionError::Overflow)
+        );
+        assert_eq!(
+            from_bigint::<U256>(&BigInt::from(-7)),
+            Err(ConversionError::Negative)
+        );
     }
 }
@@ -0,0 +1,115 @@
+//! conversions between the U256 values of this crate and RustCrypto's `k256` types, to verify
+//! signatures with `k256` or sign with keys of an existing wallet.
+//! enabled by the `k256-interop` feature.
+
+use k256::{
+    ecdsa::{Error, Signature, VerifyingKey},
+    elliptic_curve::{sec1::ToEncodedPoint, PrimeField},
+    Scalar, SecretKey,
+};
+use tfhe::integer::U256;
+
+use crate::helper::{u256_from_be_bytes, u256_to_be_bytes};
+
+pub fn u256_from_k256_scalar(scalar: &Scalar) -> U256 {
+    u256_from_be_bytes(&scalar.to_bytes())
+}
+
+/// `None` if x >= n
+pub fn u256_to_k256_scalar(x: U256) -> Option<Scalar> {
+    Scalar::from_repr(u256_to_be_bytes(x).into()).into()
+}
+
+/// secret key % n of a `k256` secret key, e.g. to encrypt it for `ecdsa_sign`
+pub fn u256_from_k256_secret_key(secret_key: &SecretKey) -> U256 {
+    u256_from_be_bytes(&secret_key.to_bytes())
+}
+
+/// affine public key `x, y` of a `k256` verifying key
+pub fn from_k256_verifying_key(verifying_key: &VerifyingKey) -> (U256, U256) {
+    let point = verifying_key.to_encoded_point(false);
+    (
+        u256_from_be_bytes(point.x().expect("uncompressed point has x")),
+        u256_from_be_bytes(point.y().expect("uncompressed point has y")),
+    )
+}
+
+/// `k256` verifying key of affine public key `x, y`, fails if the point is not on secp256k1
+pub fn to_k256_verifying_key(public_key: (U256, U256)) -> Result<VerifyingKey, Error> {
+    let mut bytes = [0u8; 65];
+    bytes[0] = 0x04;
+    bytes[1..33].copy_from_slice(&u256_to_be_bytes(public_key.0));
+    bytes[33..].copy_from_slice(&u256_to_be_bytes(public_key.1));
+    VerifyingKey::from_sec1_bytes(&bytes)
+}
+
+/// `k256` signature of ECDSA signature `r, s`, fails if r or s is 0 or not % n.
+/// `k256` only verifies low-S signatures, see `normalize_s_native`.
+pub fn to_k256_signature(r: U256, s: U256) -> Result<Signature, Error> {
+    Signature::from_scalars(u256_to_be_bytes(r), u256_to_be_bytes(s))
+}
+
+pub fn from_k256_signature(signature: &Signature) -> (U256, U256) {
+    let (r, s) = signature.split_bytes();
+    (u256_from_be_bytes(&r), u256_from_be_bytes(&s))
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::{
+        ecdsa::{signature::hazmat::PrehashVerifier, SigningKey},
+        SecretKey,
+    };
+    use tfhe::integer::U256;
+
+    use crate::{
+        ecdsa::{derive_public_key_native, ecdsa_sign_native, normalize_s_native},
+        helper::{hash_message_sha256, u256_from_hex_string, u256_to_be_bytes},
+        ops::secp256k1::prelude::{FQ_MODULO, FR_MODULO, GENERATOR, PARAMS},
+    };
+
+    use super::{
+        from_k256_signature, from_k256_verifying_key, to_k256_signature, to_k256_verifying_key,
+        u256_from_k256_scalar, u256_from_k256_secret_key, u256_to_k256_scalar,
+    };
+
+    #[test]
+    fn correct_k256_verifies_signature() {
+        let sk = u256_from_hex_string(
+            "0xc9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+        );
+        let k = u256_from_hex_string(
+            "0xa6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+        );
+        let message = hash_message_sha256(b"sample");
+        let pk = derive_public_key_native(sk, &PARAMS);
+
+        let (r, s) = ecdsa_sign_native(sk, k, message, &PARAMS);
+        let s = normalize_s_native(s, *FR_MODULO);
+        let signature = to_k256_signature(r, s).unwrap();
+        assert_eq!(from_k256_signature(&signature), (r, s));
+
+        let verifying_key = to_k256_verifying_key(pk).unwrap();
+        assert_eq!(from_k256_verifying_key(&verifying_key), pk);
+        let prehash = u256_to_be_bytes(message);
+        assert!(verifying_key.verify_prehash(&prehash, &signature).is_ok());
+        let other = u256_to_be_bytes(hash_message_sha256(b"other"));
+        assert!(verifying_key.verify_prehash(&other, &signature).is_err());
+    }
+
+    #[test]
+    fn correct_k256_keys() {
+        let secret_key = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let sk = u256_from_k256_secret_key(&secret_key);
+        let verifying_key = SigningKey::from(&secret_key).verifying_key().to_owned();
+        assert_eq!(
+            derive_public_key_native(sk, &PARAMS),
+            from_k256_verifying_key(&verifying_key)
+        );
+
+        let scalar = u256_to_k256_scalar(sk).unwrap();
+        assert_eq!(u256_from_k256_scalar(&scalar), sk);
+        assert!(u256_to_k256_scalar(*FR_MODULO).is_none());
+        assert!(to_k256_signature(U256::ZERO, U256::ONE).is_err());
+    }
+}
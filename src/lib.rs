@@ -1,28 +1,42 @@
 #![allow(unused_imports)]
 
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
 
 use ctor::ctor;
 use lazy_static::lazy_static;
 use logging_timer::Level;
+use rayon::ThreadPool;
 use tfhe::integer::ClientKey;
 
+pub mod blind;
 pub mod ecdsa;
+pub mod estimate_ops;
 pub mod helper;
+#[cfg(feature = "k256-interop")]
+pub mod k256_interop;
 pub mod numeral;
 pub mod ops;
+pub mod schnorr;
 pub mod stats;
 
 lazy_static! {
     pub static ref CLIENT_KEY: RwLock<Option<ClientKey>> = RwLock::new(None);
+    pub static ref THREAD_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
 }
 
 pub const WINDOW: usize = 6;
 
+/// expensive checks of the homomorphic results, e.g. `mod_mersenne_ctx` decrypts its input and
+/// output with the client key set by `set_client_key` and compares with the native reduction.
+/// on in the crate's own tests and with the `debug_checks` feature, off otherwise, so debug
+/// builds of dependents don't pay for a decryption per reduction. checks of the inputs stay
+/// plain `assert!`s.
+pub const DEBUG_CHECKS: bool = cfg!(any(test, feature = "debug_checks"));
+
 #[ctor]
 fn init() {
     env_logger::builder()
-        .filter_level(Level::Debug.to_level_filter())
+        .filter_level(Level::Warn.to_level_filter())
         .parse_default_env()
         .init();
 }
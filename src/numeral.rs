@@ -1,3 +1,5 @@
+use std::fmt::{Debug, Formatter};
+
 use num_bigint::BigInt;
 use tfhe::{
     core_crypto::prelude::{Numeric, UnsignedNumeric},
@@ -7,8 +9,9 @@ use tfhe::{
         ClientKey, RadixCiphertext, U256,
     },
 };
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-use crate::helper::{format, to_bigint};
+use crate::helper::{client_key, format, format_grouped, format_hex, to_bigint, KeyNotSetError};
 
 pub trait Numeral:
     Numeric
@@ -24,11 +27,25 @@ pub trait Numeral:
     + Copy
     + Sync
     + Send
+    + 'static
 {
     fn format(&self) -> String {
         format(*self)
     }
 
+    /// `format` with `sep` every 3 digits, easier to read when logging U256 values
+    fn format_grouped(&self, sep: char) -> String {
+        format_grouped(*self, sep)
+    }
+
+    fn format_hex(&self) -> String {
+        format_hex(*self)
+    }
+
+    fn encrypt(&self, client_key: &ClientKey, num_block: usize) -> RadixCiphertext {
+        client_key.encrypt_radix(*self, num_block)
+    }
+
     fn decrypt(ciphertext: &RadixCiphertext, client_key: &ClientKey) -> Self {
         client_key.decrypt_radix::<Self>(ciphertext)
     }
@@ -36,6 +53,53 @@ pub trait Numeral:
     fn decrypt_bigint(ciphertext: &RadixCiphertext, client_key: &ClientKey) -> BigInt {
         to_bigint(client_key.decrypt_radix::<U256>(ciphertext))
     }
+
+    /// decrypt with the global client key, fails if it was never set
+    fn try_decrypt(ciphertext: &RadixCiphertext) -> Result<Self, KeyNotSetError> {
+        Ok(Self::decrypt(ciphertext, &client_key()?))
+    }
+
+    /// decrypt secret material like the secret key or a nonce, zeroized when dropped
+    fn decrypt_secret(ciphertext: &RadixCiphertext, client_key: &ClientKey) -> Secret<Self> {
+        Secret::new(Self::decrypt(ciphertext, client_key))
+    }
+}
+
+/// decrypted secret value zeroized on drop, e.g. `Secret<U256>` for a secret key.
+/// copies taken with `expose` are not tracked, keep them short lived.
+#[repr(transparent)]
+pub struct Secret<P: Numeral>(P);
+
+impl<P: Numeral> Secret<P> {
+    pub fn new(value: P) -> Self {
+        Secret(value)
+    }
+
+    pub fn expose(&self) -> &P {
+        &self.0
+    }
+}
+
+impl<P: Numeral> Zeroize for Secret<P> {
+    fn zeroize(&mut self) {
+        // SAFETY: numerals are plain integers or arrays of them without drop glue
+        // and all zero bytes is the valid value 0.
+        unsafe { zeroize::zeroize_flat_type(&mut self.0) }
+    }
+}
+
+impl<P: Numeral> Drop for Secret<P> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<P: Numeral> ZeroizeOnDrop for Secret<P> {}
+
+impl<P: Numeral> Debug for Secret<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(..)")
+    }
 }
 
 impl<T> Numeral for T where
@@ -54,3 +118,76 @@ impl<T> Numeral for T where
         + Send
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind, U256},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use std::mem::MaybeUninit;
+
+    use zeroize::Zeroize;
+
+    use crate::{
+        helper::u256_from_decimal_string,
+        numeral::{Numeral, Secret},
+    };
+
+    #[test]
+    fn correct_encrypt_decrypt() {
+        let (client_key, _) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        for value in [0u8, 1, 199, u8::MAX] {
+            assert_eq!(
+                u8::decrypt(&value.encrypt(&client_key, 4), &client_key),
+                value
+            );
+        }
+        for value in [0u64, 1, 18446744073708503011, u64::MAX] {
+            assert_eq!(
+                u64::decrypt(&value.encrypt(&client_key, 32), &client_key),
+                value
+            );
+        }
+        for value in [
+            U256::ZERO,
+            U256::ONE,
+            u256_from_decimal_string(
+                "115792089237316195423570985008687907853269984665640564039457584007908834671663",
+            ),
+        ] {
+            assert_eq!(
+                U256::decrypt(&value.encrypt(&client_key, 128), &client_key),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn correct_decrypt_secret() {
+        let (client_key, _) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        let value = u256_from_decimal_string(
+            "115792089237316195423570985008687907853269984665640564039457584007908834671663",
+        );
+
+        let mut secret = U256::decrypt_secret(&value.encrypt(&client_key, 128), &client_key);
+        assert_eq!(*secret.expose(), value);
+        assert_eq!(format!("{:?}", secret), "Secret(..)");
+        secret.zeroize();
+        assert_eq!(*secret.expose(), U256::ZERO);
+
+        // drop in place and read the bytes left behind through the same raw pointer, the slot
+        // stays allocated and `Secret` is a transparent wrapper of the U256
+        let mut slot = MaybeUninit::new(Secret::new(value));
+        let ptr = slot.as_mut_ptr();
+        let left = unsafe {
+            std::ptr::drop_in_place(ptr);
+            ptr.cast::<U256>().read()
+        };
+        assert_eq!(left, U256::ZERO);
+    }
+}
@@ -1,6 +1,7 @@
 use std::time::Instant;
 
 use logging_timer::{stime, time, timer, Level};
+use num_bigint::BigInt;
 use rand::Rng;
 use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use tfhe::{
@@ -13,19 +14,36 @@ use tfhe::{
 };
 
 use crate::{
-    helper::{format, read_client_key},
+    helper::{
+        barrett_mu, bits_per_block, format, from_bigint, montgomery_params, read_client_key,
+        to_bigint,
+    },
     numeral::Numeral,
     ops::mersenne::mod_mersenne,
-    stats::{ProtocolLowOps, ProtocolStats},
+    stats::{ProtocolLowOps, ProtocolOps, ProtocolStats},
 };
 
-use self::{mersenne::mul_mod_mersenne, native::inverse_mod_native, primitive::parallel_fn};
+use self::{
+    barrett::mod_barrett,
+    mersenne::{mersenne_coeff_p, mul_mod_mersenne},
+    montgomery::{mul_mod_montgomery, to_montgomery},
+    native::inverse_mod_native,
+    primitive::parallel_fn,
+};
 
+pub mod backend;
+pub mod barrett;
+pub mod curve;
 pub mod group_jacobian;
+pub mod karatsuba;
 pub mod mersenne;
+pub mod mock;
+pub mod montgomery;
 pub mod native;
 pub mod primitive;
 pub mod secp256k1;
+pub mod secp256r1;
+pub mod testcurve;
 
 /// selector ? a : 0
 /// selector is a bit (0 or 1)
@@ -74,6 +92,28 @@ pub fn selector(
     server_key.add_parallelized(&r0, &r1)
 }
 
+/// cond ? a : b
+/// cond is a bit (0 or 1), computed as b + cond * (a - b) with a single multiplication
+pub fn select<const NB: usize>(
+    cond: &RadixCiphertext,
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let len = cond.blocks().len();
+    let cond = server_key.trim_radix_blocks_msb(cond, len - 1);
+    // a - b can underflow, work with an extra block so b + (a - b) wraps back correctly
+    let (a_expanded, mut b_expanded) = rayon::join(
+        || server_key.extend_radix_with_trivial_zero_blocks_msb(a, NB + 1 - a.blocks().len()),
+        || server_key.extend_radix_with_trivial_zero_blocks_msb(b, NB + 1 - b.blocks().len()),
+    );
+    let mut diff = server_key.sub_parallelized(&a_expanded, &b_expanded);
+    server_key.mul_assign_parallelized(&mut diff, &cond);
+    server_key.add_assign_parallelized(&mut b_expanded, &diff);
+    server_key.trim_radix_blocks_msb_assign(&mut b_expanded, 1);
+    b_expanded
+}
+
 /// turn x mod a to x mod b
 /// only if a > b and a < 2b
 #[time("trace", "Modulus Reduction")]
@@ -81,6 +121,18 @@ pub fn modulo_fast<const NB: usize, P: Numeral>(
     x: &RadixCiphertext,
     b: P,
     server_key: &ServerKey,
+) -> RadixCiphertext {
+    ProtocolStats::time_op(ProtocolOps::ModReduce, || {
+        modulo_fast_inner::<NB, _>(x, b, server_key)
+    })
+}
+
+/// `modulo_fast` without recording a `ModReduce`, the final subtraction of the reductions
+/// that record their own
+pub(crate) fn modulo_fast_inner<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    b: P,
+    server_key: &ServerKey,
 ) -> RadixCiphertext {
     let len = x.blocks().len();
     let mut x = x.clone();
@@ -114,6 +166,20 @@ pub fn inverse_mod<const NB: usize, P: Numeral>(
     inverse_mod_trim::<NB, _>(a, p, server_key)
 }
 
+/// a^-1 mod p where a*a^-1 = 1 mod p
+/// using Fermat's little theorem a^(p-2) mod p. the exponent is public,
+/// so the square and multiply ladder is unrolled over the bits of p - 2.
+/// requires bits(p - 2) - 1 square and popcount(p - 2) - 1 mul,
+/// 255 square and 248 mul for secp256k1 base field.
+#[time("debug", "Inverse Mod Fermat")]
+pub fn inverse_mod_fermat<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    pow_mod_scalar::<NB, _>(a, &(to_bigint(p) - 2), p, server_key)
+}
+
 #[inline]
 pub fn inverse_mods<const NB: usize, P: Numeral>(
     a: &[RadixCiphertext],
@@ -216,6 +282,67 @@ pub fn inverse_mod_trim<const NB: usize, P: Numeral>(
     inv
 }
 
+/// x < p as an encrypted 1 block bit, e.g. the condition of `select` for range checks
+pub fn lt_scalar<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let is_lt = server_key.scalar_lt_parallelized(x, p);
+    let mut radix_is_lt: RadixCiphertext = is_lt.into_radix(NB - 1, server_key);
+    let len = radix_is_lt.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_lt, len - 1);
+    radix_is_lt
+}
+
+/// x >= p as an encrypted 1 block bit, e.g. the condition of `select` for range checks
+pub fn ge_scalar<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let is_ge = server_key.scalar_ge_parallelized(x, p);
+    let mut radix_is_ge: RadixCiphertext = is_ge.into_radix(NB - 1, server_key);
+    let len = radix_is_ge.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_ge, len - 1);
+    radix_is_ge
+}
+
+/// x == 0 as an encrypted 1 block bit, e.g. to flag an invalid r or s.
+/// the blocks are or-ed together first so only one block is compared with 0.
+pub fn is_zero<const NB: usize>(x: &RadixCiphertext, server_key: &ServerKey) -> RadixCiphertext {
+    let blocks = x
+        .blocks()
+        .iter()
+        .map(|block| RadixCiphertext::from_blocks(vec![block.clone()]))
+        .collect::<Vec<_>>();
+    let any_bit = parallel_fn(&blocks, |a, b| server_key.bitor_parallelized(a, b));
+    let is_zero = server_key.scalar_eq_parallelized(&any_bit, 0);
+    let mut radix_is_zero: RadixCiphertext = is_zero.into_radix(NB - 1, server_key);
+    let len = radix_is_zero.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_zero, len - 1);
+    radix_is_zero
+}
+
+/// a == b mod p as an encrypted 1 block bit, a and b can be any NB blocks value.
+/// both are reduced first so residues differing by a multiple of p compare equal.
+pub fn eq_mod<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let (a_mod, b_mod) = rayon::join(
+        || modulo_div_rem::<NB, _>(a, p, server_key),
+        || modulo_div_rem::<NB, _>(b, p, server_key),
+    );
+    let is_eq = server_key.eq_parallelized(&a_mod, &b_mod);
+    let mut radix_is_eq: RadixCiphertext = is_eq.into_radix(NB - 1, server_key);
+    let len = radix_is_eq.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_eq, len - 1);
+    radix_is_eq
+}
+
 /// a + b mod p
 #[time("debug", "Add Mod")]
 pub fn add_mod<const NB: usize, P: Numeral>(
@@ -259,7 +386,46 @@ pub fn sub_mod<const NB: usize, P: Numeral>(
     a_expanded
 }
 
+/// -x mod p = p - x for x in [0, p), 0 stays 0
+pub fn neg_mod<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let (neg, is_non_zero) = rayon::join(
+        || server_key.sub_parallelized(&server_key.create_trivial_radix(p, NB), x),
+        || server_key.scalar_ne_parallelized(x, 0),
+    );
+    let radix_is_non_zero: RadixCiphertext = is_non_zero.into_radix(NB - 1, server_key);
+    selector_zero(&neg, &radix_is_non_zero, server_key)
+}
+
+/// modular reduction used by `mul_mod_with`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReductionStrategy {
+    /// p = 2^n - c with small c, see `mod_mersenne`
+    Mersenne,
+    /// any p, see `mod_barrett`
+    Barrett,
+    /// odd p, see `montgomery_reduce`. takes an extra reduction to bring one operand
+    /// into Montgomery form, so it only pays off for chained multiplications
+    Montgomery,
+}
+
+impl ReductionStrategy {
+    /// Mersenne if c = 2^n - p has less than n/2 bits, Barrett otherwise
+    pub fn for_modulus<P: Numeral>(p: P) -> Self {
+        let (n, c) = mersenne_coeff_p(p);
+        if (c.bits() as u32) < n / 2 {
+            ReductionStrategy::Mersenne
+        } else {
+            ReductionStrategy::Barrett
+        }
+    }
+}
+
 /// a * b mod p
+/// the reduction is picked from p with `ReductionStrategy::for_modulus`
 #[time("debug", "Mul Mod")]
 pub fn mul_mod<const NB: usize, P: Numeral>(
     a: &RadixCiphertext,
@@ -268,11 +434,36 @@ pub fn mul_mod<const NB: usize, P: Numeral>(
     server_key: &ServerKey,
 ) -> RadixCiphertext {
     let start_ops = Instant::now();
-    let res = mul_mod_mersenne::<NB, _>(a, b, p, server_key);
+    let res = mul_mod_with::<NB, _>(a, b, p, ReductionStrategy::for_modulus(p), server_key);
     ProtocolStats::add_time(ProtocolLowOps::MulMod, start_ops.elapsed().as_secs_f32());
     res
 }
 
+/// a * b mod p with the given reduction strategy
+pub fn mul_mod_with<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    p: P,
+    strategy: ReductionStrategy,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    match strategy {
+        ReductionStrategy::Mersenne => mul_mod_mersenne::<NB, _>(a, b, p, server_key),
+        ReductionStrategy::Barrett => {
+            let mut a_expanded = server_key.extend_radix_with_trivial_zero_blocks_msb(a, NB);
+            server_key.mul_assign_parallelized(&mut a_expanded, b);
+            mod_barrett::<NB, _>(&a_expanded, p, &barrett_mu(&to_bigint(p)), server_key)
+        }
+        ReductionStrategy::Montgomery => {
+            // (a * R) * b * R^-1 = a * b
+            let (_r, n_prime) = montgomery_params(&to_bigint(p), NB, bits_per_block(a));
+            let n_prime = from_bigint::<P>(&n_prime).expect("n' < R fits P");
+            let a_mont = to_montgomery::<NB, _>(a, p, n_prime, server_key);
+            mul_mod_montgomery::<NB, _>(&a_mont, b, p, n_prime, server_key)
+        }
+    }
+}
+
 /// a * b mod p where b is a constant
 /// slower than 12 `add_mod`
 pub fn mul_mod_constant<const NB: usize, P: Numeral>(
@@ -288,6 +479,32 @@ pub fn mul_mod_constant<const NB: usize, P: Numeral>(
     mod_mersenne::<NB, _>(&a_expanded, p, server_key)
 }
 
+/// a * k mod p for a small public `k` <= 16 e.g. 3x, 4x and 8x of the point formulas.
+/// the shifted copies of a for the set bits of k are added then reduced once with `mod_mersenne`,
+/// a power of 2 is a single shift. expect a < p and p >= 16 so that a * k < p^2
+pub fn scalar_mul_small_mod<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    k: u8,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    assert!(k <= 16, "k must be at most 16");
+    if k == 0 {
+        return server_key.create_trivial_radix(0, NB);
+    }
+    // a * 16 < 2^5 * p
+    let bits_per_block = bits_per_block(a);
+    let extra_blocks = (5 + bits_per_block - 1) / bits_per_block;
+    let a = server_key.extend_radix_with_trivial_zero_blocks_msb(a, extra_blocks);
+    let ka = (0..5)
+        .filter(|i| (k >> i) & 1 == 1)
+        .map(|i| server_key.scalar_left_shift_parallelized(&a, i as u64))
+        .reduce(|acc, term| server_key.add_parallelized(&acc, &term))
+        .expect("k > 0 has a set bit");
+
+    mod_mersenne::<NB, _>(&ka, p, server_key)
+}
+
 /// a^2 mod p
 #[inline(always)]
 #[time("debug", "Square mod")]
@@ -297,7 +514,7 @@ pub fn square_mod<const NB: usize, P: Numeral>(
     server_key: &ServerKey,
 ) -> RadixCiphertext {
     let start_ops = Instant::now();
-    let res = mul_mod_mersenne::<NB, _>(a, a, p, server_key);
+    let res = mul_mod_with::<NB, _>(a, a, p, ReductionStrategy::for_modulus(p), server_key);
 
     ProtocolStats::add_time(ProtocolLowOps::SquareMod, start_ops.elapsed().as_secs_f32());
 
@@ -305,6 +522,8 @@ pub fn square_mod<const NB: usize, P: Numeral>(
 }
 
 /// a*2 mod p
+/// a left shift by 1 reduced with a single conditional subtraction `modulo_fast` since 2a < 2p,
+/// same result as `add_mod(a, a)`. expect a < p
 #[inline(always)]
 #[time("debug", "Double mod")]
 pub fn double_mod<const NB: usize, P: Numeral>(
@@ -361,13 +580,38 @@ pub fn pow_mod<const NB: usize, P: Numeral>(
     res
 }
 
+/// a^e mod p with a public exponent `e`
+/// left to right square and multiply unrolled over the bits of `e`,
+/// so no homomorphic selection is needed unlike `pow_mod`.
+#[time("debug", "Pow mod scalar")]
+pub fn pow_mod_scalar<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    e: &BigInt,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    if e.bits() == 0 {
+        return server_key.create_trivial_radix(1, NB);
+    }
+    let mut res = a.clone();
+    for i in (0..e.bits() - 1).rev() {
+        let _tmr = timer!(Level::Trace; "Pow Mod Scalar", "Bit {}", i);
+        res = square_mod::<NB, _>(&res, p, server_key);
+        if e.bit(i) {
+            res = mul_mod::<NB, _>(&res, a, p, server_key);
+        }
+    }
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Instant;
 
+    use num_bigint::BigInt;
     use rand::{rngs::OsRng, thread_rng, Rng};
     use tfhe::{
-        integer::{keycache::IntegerKeyCache, IntegerKeyKind, U256},
+        integer::{keycache::IntegerKeyCache, IntegerCiphertext, IntegerKeyKind, U256},
         shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
     };
 
@@ -375,18 +619,130 @@ mod tests {
         helper::set_client_key,
         numeral::Numeral,
         ops::{
-            add_mod, double_mod, inverse_mod, inverse_mods,
+            add_mod, double_mod, eq_mod, ge_scalar, inverse_mod, inverse_mod_fermat, inverse_mods,
+            is_zero, lt_scalar,
             mersenne::mod_mersenne,
-            modulo_fast, mul_mod, mul_mod_constant,
+            modulo_fast, mul_mod, mul_mod_constant, mul_mod_with,
             native::{
                 add_mod_native, double_mod_native, inverse_mod_native, modulo_native,
-                mul_mod_native, square_mod_native, sub_mod_native,
+                mul_mod_native, pow_mod_native, square_mod_native, sub_mod_native,
             },
-            square_mod, sub_mod,
+            neg_mod, pow_mod_scalar, scalar_mul_small_mod, secp256k1, secp256r1, select,
+            square_mod, sub_mod, ReductionStrategy,
         },
         CLIENT_KEY,
     };
 
+    #[test]
+    fn correct_is_zero() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        // 64 only sets the most significant block
+        for (x, expected) in [(0u8, 1u8), (1, 0), (64, 0), (u8::MAX, 0)] {
+            let res = is_zero::<NUM_BLOCK>(&client_key.encrypt_radix(x, NUM_BLOCK), &server_key);
+            assert_eq!(res.blocks().len(), 1);
+            assert_eq!(client_key.decrypt_radix::<u8>(&res), expected, "{} == 0", x);
+        }
+    }
+
+    #[test]
+    fn correct_eq_mod() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let p: u8 = 199;
+
+        let mut pairs = vec![
+            (10u8, 209u8),
+            (56, 255),
+            (0, 199),
+            (198, 198),
+            (198, 199),
+            (0, 1),
+        ];
+        pairs.extend((0..4).map(|_| (OsRng.gen::<u8>(), OsRng.gen::<u8>())));
+        for (a, b) in pairs {
+            let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+            let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+            let is_eq = eq_mod::<NUM_BLOCK, _>(&enc_a, &enc_b, p, &server_key);
+            assert_eq!(is_eq.blocks().len(), 1);
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&is_eq),
+                (a % p == b % p) as u8,
+                "{} == {} mod {}",
+                a,
+                b,
+                p
+            );
+        }
+    }
+
+    #[test]
+    fn correct_lt_ge_scalar() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let p: u8 = 199;
+
+        for x in [0u8, p - 1, p, p + 1, u8::MAX] {
+            let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+            let (is_lt, is_ge) = rayon::join(
+                || lt_scalar::<NUM_BLOCK, _>(&enc_x, p, &server_key),
+                || ge_scalar::<NUM_BLOCK, _>(&enc_x, p, &server_key),
+            );
+            assert_eq!(is_lt.blocks().len(), 1);
+            assert_eq!(is_ge.blocks().len(), 1);
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&is_lt),
+                (x < p) as u8,
+                "{} < {}",
+                x,
+                p
+            );
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&is_ge),
+                (x >= p) as u8,
+                "{} >= {}",
+                x,
+                p
+            );
+
+            // conditional subtraction x >= p ? x - p : x
+            let enc_sub = server_key.scalar_sub_parallelized(&enc_x, p);
+            let reduced = select::<NUM_BLOCK>(&is_ge, &enc_sub, &enc_x, &server_key);
+            assert_eq!(client_key.decrypt_radix::<u8>(&reduced), x % p);
+        }
+    }
+
+    #[test]
+    fn correct_select() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 128;
+        let a = U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>()));
+        let b = U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>()));
+        let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+        let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+
+        let enc_c = select::<NUM_BLOCK>(
+            &client_key.encrypt_radix(1u8, 1),
+            &enc_a,
+            &enc_b,
+            &server_key,
+        );
+        assert_eq!(a, U256::decrypt(&enc_c, &client_key));
+
+        let enc_c = select::<NUM_BLOCK>(
+            &client_key.encrypt_radix(0u8, 1),
+            &enc_a,
+            &enc_b,
+            &server_key,
+        );
+        assert_eq!(b, U256::decrypt(&enc_c, &client_key));
+    }
+
     #[test]
     fn correct_fast_mod_reduc() {
         let (client_key, server_key) =
@@ -546,6 +902,27 @@ mod tests {
         assert_eq!(e as u8, client_key.decrypt_radix::<u8>(&enc_e));
     }
 
+    #[test]
+    fn correct_neg_mod() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let p: u8 = 251;
+
+        for x in [0, 1, 2, 125, 126, 249, 250] {
+            let enc_neg =
+                neg_mod::<NUM_BLOCK, _>(&client_key.encrypt_radix(x, NUM_BLOCK), p, &server_key);
+            assert_eq!(enc_neg.blocks().len(), NUM_BLOCK);
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&enc_neg),
+                sub_mod_native(0, x, p),
+                "-{} mod {}",
+                x,
+                p
+            );
+        }
+    }
+
     #[test]
     fn correct_sub_mod_random() {
         let (client_key, server_key) =
@@ -603,6 +980,62 @@ mod tests {
         assert_eq!(c as u8, client_key.decrypt_radix::<u8>(&enc_c));
     }
 
+    #[test]
+    fn correct_double_mod_matches_add_mod() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        for p in [251u8, 211, 199] {
+            for a in [0, 1, p / 2, p / 2 + 1, p - 1, OsRng.gen_range(0..p)] {
+                let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+                let doubled = double_mod::<NUM_BLOCK, _>(&enc_a, p, &server_key);
+                let added = add_mod::<NUM_BLOCK, _>(&enc_a, &enc_a, p, &server_key);
+                assert_eq!(doubled.blocks().len(), NUM_BLOCK);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&doubled),
+                    client_key.decrypt_radix::<u8>(&added),
+                    "2 * {} mod {}",
+                    a,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn correct_scalar_mul_small_mod() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        for p in [251u8, 199] {
+            let a = OsRng.gen_range(0..p);
+            let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+            for k in 2..=16u8 {
+                let res = scalar_mul_small_mod::<NUM_BLOCK, _>(&enc_a, k, p, &server_key);
+                assert_eq!(res.blocks().len(), NUM_BLOCK);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&res),
+                    mul_mod_native(a, k, p),
+                    "{} * {} mod {}",
+                    a,
+                    k,
+                    p
+                );
+            }
+        }
+
+        let enc_max = client_key.encrypt_radix(250u8, NUM_BLOCK);
+        let res = scalar_mul_small_mod::<NUM_BLOCK, _>(&enc_max, 16, 251, &server_key);
+        assert_eq!(
+            client_key.decrypt_radix::<u8>(&res),
+            mul_mod_native(250, 16, 251)
+        );
+        let res = scalar_mul_small_mod::<NUM_BLOCK, _>(&enc_max, 0, 251, &server_key);
+        assert_eq!(client_key.decrypt_radix::<u8>(&res), 0);
+    }
+
     #[test]
     fn correct_square_mod() {
         let (client_key, server_key) =
@@ -763,6 +1196,32 @@ mod tests {
         assert_eq!(c, client_key.decrypt_radix::<u8>(&enc_c));
     }
 
+    #[test]
+    fn correct_inverse_mod_fermat() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let p: u8 = 157;
+        let a = 8;
+        let b = OsRng.gen_range(1..p);
+
+        let c = inverse_mod_native(a, p);
+        let enc_c = inverse_mod_fermat::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(a, NUM_BLOCK),
+            p,
+            &server_key,
+        );
+        assert_eq!(c, client_key.decrypt_radix::<u8>(&enc_c));
+
+        let d = inverse_mod_native(b, p);
+        let enc_d = inverse_mod_fermat::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(b, NUM_BLOCK),
+            p,
+            &server_key,
+        );
+        assert_eq!(d, client_key.decrypt_radix::<u8>(&enc_d));
+    }
+
     #[test]
     fn correct_inverse_mods() {
         let (client_key, server_key) =
@@ -814,4 +1273,133 @@ mod tests {
         assert_eq!(a, client_key.decrypt_radix::<u8>(&results[0]));
         assert_eq!(b, client_key.decrypt_radix::<u8>(&results[1]));
     }
+
+    #[test]
+    fn correct_pow_mod_scalar() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let p: u8 = 251;
+
+        for (a, e) in [(8u8, 0u8), (8, 1), (123, 2), (250, 125), (17, 249), (0, 7)] {
+            let enc_c = pow_mod_scalar::<NUM_BLOCK, _>(
+                &client_key.encrypt_radix(a, NUM_BLOCK),
+                &BigInt::from(e),
+                p,
+                &server_key,
+            );
+            assert_eq!(
+                pow_mod_native(a, e, p),
+                client_key.decrypt_radix::<u8>(&enc_c)
+            );
+        }
+    }
+
+    #[test]
+    fn correct_pow_mod_scalar_secp256k1() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 128;
+        let p = *secp256k1::prelude::FQ_MODULO;
+        let a = U256::from((
+            0x029bfcdb2dce28d959f2815b16f81798,
+            0x79be667ef9dcbbac55a06295ce870b07,
+        ));
+        let e = U256::from(5u8);
+
+        let now = Instant::now();
+        let enc_c = pow_mod_scalar::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(a, NUM_BLOCK),
+            &BigInt::from(5),
+            p,
+            &server_key,
+        );
+        println!("pow mod scalar done in {:.2}s", now.elapsed().as_secs_f64());
+        assert_eq!(
+            pow_mod_native(a, e, p),
+            client_key.decrypt_radix::<U256>(&enc_c)
+        );
+    }
+
+    #[test]
+    fn correct_reduction_strategy_for_modulus() {
+        assert_eq!(
+            ReductionStrategy::for_modulus(251u8),
+            ReductionStrategy::Mersenne
+        );
+        assert_eq!(
+            ReductionStrategy::for_modulus(157u8),
+            ReductionStrategy::Barrett
+        );
+        assert_eq!(
+            ReductionStrategy::for_modulus(*secp256k1::prelude::FQ_MODULO),
+            ReductionStrategy::Mersenne
+        );
+        assert_eq!(
+            ReductionStrategy::for_modulus(*secp256r1::prelude::FQ_MODULO),
+            ReductionStrategy::Barrett
+        );
+    }
+
+    #[test]
+    fn correct_mul_mod_with() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        for p in [251u8, 157] {
+            let a = OsRng.gen_range(0..p);
+            let b = OsRng.gen_range(0..p);
+            let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+            let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+
+            for strategy in [
+                ReductionStrategy::Mersenne,
+                ReductionStrategy::Barrett,
+                ReductionStrategy::Montgomery,
+            ] {
+                let enc_c = mul_mod_with::<NUM_BLOCK, _>(&enc_a, &enc_b, p, strategy, &server_key);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&enc_c),
+                    mul_mod_native(a, b, p),
+                    "{:?} mod {}",
+                    strategy,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn correct_add_sub_mod_boundary() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let p: u8 = 251;
+
+        for (a, b) in [
+            (0, 0),
+            (0, p - 1),
+            (p - 1, 0),
+            (p - 1, 1),
+            (1, p - 1),
+            (p - 1, p - 1),
+            (p - 2, p - 1),
+        ] {
+            let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+            let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+            let (enc_add, enc_sub) = rayon::join(
+                || add_mod::<NUM_BLOCK, _>(&enc_a, &enc_b, p, &server_key),
+                || sub_mod::<NUM_BLOCK, _>(&enc_a, &enc_b, p, &server_key),
+            );
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&enc_add),
+                add_mod_native(a, b, p)
+            );
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&enc_sub),
+                sub_mod_native(a, b, p)
+            );
+        }
+    }
 }
@@ -0,0 +1,90 @@
+use num_bigint::BigInt;
+use tfhe::integer::{IntegerCiphertext, RadixCiphertext, ServerKey};
+
+use crate::{helper::bits_per_block, numeral::Numeral};
+
+use super::mersenne::{scalar_mul_sparse, scalar_mul_trivial};
+
+/// the radix integer operations the reductions are written against, implemented by
+/// `RadixCiphertext` and by the plaintext `MockInt` to test the algorithms without FHE.
+/// values are `num_blocks` blocks of `bits_per_block` bits and wrap around like
+/// `RadixCiphertext`, every result has the blocks of `self`.
+pub trait FheInt: Clone + Send + Sync {
+    /// the key the operations run with, `ServerKey` for `RadixCiphertext`
+    type Key: Sync;
+
+    fn num_blocks(&self) -> usize;
+
+    fn bits_per_block(&self) -> usize;
+
+    fn scalar_left_shift(&self, shift: u64, key: &Self::Key) -> Self;
+
+    fn scalar_right_shift(&self, shift: u64, key: &Self::Key) -> Self;
+
+    /// self * c truncated to the blocks of self
+    fn scalar_mul(&self, c: &BigInt, key: &Self::Key) -> Self;
+
+    fn add(&self, other: &Self, key: &Self::Key) -> Self;
+
+    fn sub(&self, other: &Self, key: &Self::Key) -> Self;
+
+    /// self - p if self >= p, the final subtraction of a value below 2p
+    fn sub_scalar_if_ge<P: Numeral>(&self, p: P, key: &Self::Key) -> Self;
+
+    /// drop `num_blocks` most significant blocks
+    fn trim_msb(&self, num_blocks: usize, key: &Self::Key) -> Self;
+
+    /// add `num_blocks` zero blocks on the most significant side
+    fn extend_msb(&self, num_blocks: usize, key: &Self::Key) -> Self;
+}
+
+impl FheInt for RadixCiphertext {
+    type Key = ServerKey;
+
+    fn num_blocks(&self) -> usize {
+        self.blocks().len()
+    }
+
+    fn bits_per_block(&self) -> usize {
+        bits_per_block(self)
+    }
+
+    fn scalar_left_shift(&self, shift: u64, key: &ServerKey) -> Self {
+        key.scalar_left_shift_parallelized(self, shift)
+    }
+
+    fn scalar_right_shift(&self, shift: u64, key: &ServerKey) -> Self {
+        key.scalar_right_shift_parallelized(self, shift)
+    }
+
+    fn scalar_mul(&self, c: &BigInt, key: &ServerKey) -> Self {
+        match c.bits() > 128 {
+            true => scalar_mul_trivial(self, c, key),
+            false => scalar_mul_sparse(self, c, key),
+        }
+    }
+
+    fn add(&self, other: &Self, key: &ServerKey) -> Self {
+        key.add_parallelized(self, other)
+    }
+
+    fn sub(&self, other: &Self, key: &ServerKey) -> Self {
+        key.sub_parallelized(self, other)
+    }
+
+    fn sub_scalar_if_ge<P: Numeral>(&self, p: P, key: &ServerKey) -> Self {
+        let len = self.blocks().len();
+        let is_ge = key.scalar_ge_parallelized(self, p);
+        let mut to_sub: RadixCiphertext = is_ge.into_radix(len, key);
+        key.scalar_mul_assign_parallelized(&mut to_sub, p);
+        key.sub_parallelized(self, &to_sub)
+    }
+
+    fn trim_msb(&self, num_blocks: usize, key: &ServerKey) -> Self {
+        key.trim_radix_blocks_msb(self, num_blocks)
+    }
+
+    fn extend_msb(&self, num_blocks: usize, key: &ServerKey) -> Self {
+        key.extend_radix_with_trivial_zero_blocks_msb(self, num_blocks)
+    }
+}
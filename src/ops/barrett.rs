@@ -0,0 +1,126 @@
+use logging_timer::time;
+use num_bigint::BigInt;
+use tfhe::integer::{IntegerCiphertext, RadixCiphertext, ServerKey, U512};
+
+use crate::{
+    helper::{bits_per_block, from_bigint, to_bigint},
+    numeral::Numeral,
+    stats::{ProtocolOps, ProtocolStats},
+};
+
+use super::modulo_fast_inner;
+
+/// native x mod p using Barrett reduction with `mu` from `barrett_mu`
+/// expect x < p^2
+pub fn mod_barrett_native(x: &BigInt, p: &BigInt, mu: &BigInt) -> BigInt {
+    let k = p.bits();
+    // q = x * mu / 2^(2k) is at most 1 less than x / p
+    let q = (x * mu) >> (2 * k);
+    let r = x - q * p;
+
+    if &r >= p {
+        r - p
+    } else {
+        r
+    }
+}
+
+/// homomorphic x mod p using Barrett reduction with `mu` from `barrett_mu`
+/// expect x < p^2
+#[time("trace", "Modulus Reduction Barrett")]
+pub fn mod_barrett<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    mu: &BigInt,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    ProtocolStats::time_op(ProtocolOps::ModReduce, || {
+        mod_barrett_inner::<NB, _>(x, p, mu, server_key)
+    })
+}
+
+fn mod_barrett_inner<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    mu: &BigInt,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let k = to_bigint(p).bits();
+    // x * mu must fit in NB*2 + mu_blocks blocks
+    let bits_per_block = bits_per_block(x);
+    let mu_blocks = (mu.bits() as usize + bits_per_block - 1) / bits_per_block;
+    let x = server_key
+        .extend_radix_with_trivial_zero_blocks_msb(x, (NB * 2).saturating_sub(x.blocks().len()));
+    let mut x = server_key.extend_radix_with_trivial_zero_blocks_msb(&x, mu_blocks);
+
+    // q = x * mu / 2^(2k)
+    let mut q =
+        server_key.scalar_mul_parallelized(&x, from_bigint::<U512>(mu).expect("mu fits U512"));
+    server_key.scalar_right_shift_assign_parallelized(&mut q, 2 * k);
+    // r = x - q * p < 2p
+    server_key.sub_assign_parallelized(&mut x, &server_key.scalar_mul_parallelized(&q, p));
+    let len = x.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut x, len - (NB + 1));
+
+    modulo_fast_inner::<NB, _>(&x, p, server_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand::{rngs::OsRng, Rng};
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use crate::{
+        helper::{barrett_mu, to_bigint},
+        ops::{
+            barrett::{mod_barrett, mod_barrett_native},
+            native::modulo_native,
+            secp256k1::prelude::FR_MODULO,
+        },
+    };
+
+    #[test]
+    fn correct_barrett_native_mod() {
+        let p = to_bigint(*FR_MODULO);
+        let mu = barrett_mu(&p);
+        for _ in 0..100 {
+            let a = BigInt::from_bytes_le(num_bigint::Sign::Plus, &OsRng.gen::<[u8; 32]>()) % &p;
+            let b = BigInt::from_bytes_le(num_bigint::Sign::Plus, &OsRng.gen::<[u8; 32]>()) % &p;
+            let x = &a * &b;
+            assert_eq!(mod_barrett_native(&x, &p, &mu), x % &p);
+        }
+    }
+
+    #[test]
+    fn correct_barrett_mod_reduc() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let p: u8 = 251;
+        let mu = barrett_mu(&to_bigint(p));
+        let a: u16 = 62999;
+        let b: u16 = OsRng.gen_range(0..(p as u16).pow(2));
+
+        let c = modulo_native(a, p as u16);
+        let enc_c = mod_barrett::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(a, NUM_BLOCK * 2),
+            p,
+            &mu,
+            &server_key,
+        );
+        assert_eq!(c as u8, client_key.decrypt_radix::<u8>(&enc_c));
+
+        let d = modulo_native(b, p as u16);
+        let enc_d = mod_barrett::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(b, NUM_BLOCK * 2),
+            p,
+            &mu,
+            &server_key,
+        );
+        assert_eq!(d as u8, client_key.decrypt_radix::<u8>(&enc_d));
+    }
+}
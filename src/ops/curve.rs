@@ -0,0 +1,272 @@
+use tfhe::integer::{IntegerCiphertext, RadixCiphertext, ServerKey};
+
+use crate::numeral::Numeral;
+
+use super::{
+    add_mod,
+    group_jacobian::{group_projective_into_affine_native, scalar_mul_native},
+    mul_mod, mul_mod_constant,
+    native::{add_mod_native, mul_mod_native, square_mod_native},
+    square_mod,
+};
+
+/// short Weierstrass curve y^2 = x^3 + a*x + b % `p`
+/// with prime subgroup generator `generator` of order `n`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurveParams<P: Numeral> {
+    pub a: P,
+    pub b: P,
+    /// base prime field modulus
+    pub p: P,
+    /// scalar prime field modulus, the order of the generator
+    pub n: P,
+    pub generator: (P, P),
+}
+
+/// native affine point. the identity has no affine coordinates, it is kept as a flag and
+/// the (0, 0) tuple used by the `_native` group functions is read as the identity too.
+#[derive(Clone, Copy, Debug)]
+pub struct AffinePoint<P: Numeral> {
+    x: P,
+    y: P,
+    infinity: bool,
+}
+
+impl<P: Numeral> AffinePoint<P> {
+    pub fn new(x: P, y: P) -> Self {
+        AffinePoint {
+            x,
+            y,
+            infinity: false,
+        }
+    }
+
+    pub fn identity() -> Self {
+        AffinePoint {
+            x: P::ZERO,
+            y: P::ZERO,
+            infinity: true,
+        }
+    }
+
+    /// jacobian `x, y, z` % `p` into affine, any point with z = 0 is the identity
+    pub fn from_projective_native(x: P, y: P, z: P, p: P) -> Self {
+        match z == P::ZERO {
+            true => AffinePoint::identity(),
+            false => group_projective_into_affine_native(x, y, z, p).into(),
+        }
+    }
+
+    /// x coordinate, 0 for the identity
+    pub fn x(&self) -> P {
+        match self.is_identity() {
+            true => P::ZERO,
+            false => self.x,
+        }
+    }
+
+    /// y coordinate, 0 for the identity
+    pub fn y(&self) -> P {
+        match self.is_identity() {
+            true => P::ZERO,
+            false => self.y,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.infinity || (self.x == P::ZERO && self.y == P::ZERO)
+    }
+}
+
+impl<P: Numeral> PartialEq for AffinePoint<P> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.is_identity(), other.is_identity()) {
+            (true, true) => true,
+            (false, false) => self.x == other.x && self.y == other.y,
+            _ => false,
+        }
+    }
+}
+
+impl<P: Numeral> Eq for AffinePoint<P> {}
+
+impl<P: Numeral> From<(P, P)> for AffinePoint<P> {
+    fn from(point: (P, P)) -> Self {
+        AffinePoint::new(point.0, point.1)
+    }
+}
+
+impl<P: Numeral> From<AffinePoint<P>> for (P, P) {
+    fn from(point: AffinePoint<P>) -> Self {
+        (point.x(), point.y())
+    }
+}
+
+/// check affine point `x, y` % `p` satisfies y^2 = x^3 + a*x + b
+pub fn is_on_curve_native<P: Numeral>(point: (P, P), params: &CurveParams<P>) -> bool {
+    let p = params.p;
+    let (x, y) = point;
+    if x >= p || y >= p {
+        return false;
+    }
+    let y2 = square_mod_native(y, p);
+    let x3 = mul_mod_native(square_mod_native(x, p), x, p);
+    let ax = mul_mod_native(params.a, x, p);
+
+    y2 == add_mod_native(add_mod_native(x3, ax, p), params.b, p)
+}
+
+/// check affine point `x, y` is in the subgroup of order `n` generated by `params.generator`,
+/// i.e. n * point is the identity. every curve point passes when the cofactor is 1 like
+/// secp256k1, still a cheap sanity check on decompressed or recovered points.
+/// expects a point on the curve, see `is_on_curve_native`.
+pub fn is_in_subgroup_native<P: Numeral>(point: (P, P), params: &CurveParams<P>) -> bool {
+    scalar_mul_native(params.n, point, params) == (P::ZERO, P::ZERO)
+}
+
+/// homomorphic check affine point `x, y` % `p` satisfies y^2 = x^3 + a*x + b
+/// returns an encrypted 1 block bit, 1 if the point is on the curve
+pub fn is_on_curve<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    y: &RadixCiphertext,
+    params: &CurveParams<P>,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let p = params.p;
+    let (y2, rhs) = rayon::join(
+        || square_mod::<NB, _>(y, p, server_key),
+        || {
+            let (x3, ax) = rayon::join(
+                || mul_mod::<NB, _>(&square_mod::<NB, _>(x, p, server_key), x, p, server_key),
+                || match params.a == P::ZERO {
+                    true => server_key.create_trivial_radix(0, NB),
+                    false => mul_mod_constant::<NB, _>(x, params.a, p, server_key),
+                },
+            );
+            add_mod::<NB, _>(
+                &add_mod::<NB, _>(&x3, &ax, p, server_key),
+                &server_key.create_trivial_radix(params.b, NB),
+                p,
+                server_key,
+            )
+        },
+    );
+    let is_on_curve = server_key.eq_parallelized(&y2, &rhs);
+    let mut radix_is_on_curve: RadixCiphertext = is_on_curve.into_radix(NB - 1, server_key);
+    let len = radix_is_on_curve.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_on_curve, len - 1);
+
+    radix_is_on_curve
+}
+
+#[cfg(test)]
+mod tests {
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind, U256},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use crate::ops::{
+        group_jacobian::{
+            group_affine_add_native, group_projective_scalar_mul_native, scalar_mul_native,
+        },
+        secp256k1, secp256r1, testcurve,
+    };
+
+    use super::{is_in_subgroup_native, is_on_curve, is_on_curve_native, AffinePoint, CurveParams};
+
+    #[test]
+    fn correct_affine_point() {
+        let params = testcurve::PARAMS;
+        let (gx, gy) = params.generator;
+        let g = AffinePoint::from(params.generator);
+        assert_eq!((g.x(), g.y()), (2, 52));
+        assert!(!g.is_identity());
+        assert_eq!(g, AffinePoint::new(2, 52));
+        assert_ne!(g, AffinePoint::new(2, 75));
+        assert_ne!(g, AffinePoint::identity());
+
+        // 3G from the affine and the projective native functions
+        let (x, y, z) = group_projective_scalar_mul_native(gx, gy, 3, params.p);
+        assert_eq!(
+            AffinePoint::from_projective_native(x, y, z, params.p),
+            AffinePoint::from(scalar_mul_native(3, params.generator, &params))
+        );
+
+        // the flag, the (0, 0) tuple and any z = 0 point are the same identity
+        let g_neg = AffinePoint::new(gx, params.p - gy);
+        let sum = group_affine_add_native(g.into(), g_neg.into(), params.a, params.p);
+        let identities = [
+            AffinePoint::identity(),
+            AffinePoint::from(sum),
+            AffinePoint::from(scalar_mul_native(params.n, params.generator, &params)),
+            AffinePoint::from_projective_native(gx, gy, 0, params.p),
+        ];
+        for identity in identities {
+            assert!(identity.is_identity());
+            assert_eq!(identity, AffinePoint::identity());
+            assert_eq!(<(u8, u8)>::from(identity), (0, 0));
+            assert_ne!(identity, g);
+        }
+    }
+
+    #[test]
+    fn correct_is_on_curve_native() {
+        let params = testcurve::PARAMS;
+        assert!(is_on_curve_native(params.generator, &params));
+        assert!(!is_on_curve_native((2, 53), &params));
+        assert!(!is_on_curve_native((129, 52), &params));
+
+        for params in [*secp256k1::prelude::PARAMS, *secp256r1::prelude::PARAMS] {
+            let (x, y) = params.generator;
+            assert!(is_on_curve_native((x, y), &params));
+            assert!(!is_on_curve_native((x, y + U256::ONE), &params));
+            assert!(!is_on_curve_native((x + U256::ONE, y), &params));
+        }
+    }
+
+    #[test]
+    fn correct_is_in_subgroup_native() {
+        assert!(is_in_subgroup_native(
+            testcurve::PARAMS.generator,
+            &testcurve::PARAMS
+        ));
+        for params in [*secp256k1::prelude::PARAMS, *secp256r1::prelude::PARAMS] {
+            assert!(is_in_subgroup_native(params.generator, &params));
+        }
+
+        // y^2 = x^3 + 2x + 7 % 101 has 106 points, cofactor 2 over the subgroup of order 53
+        let params = CurveParams {
+            a: 2u8,
+            b: 7,
+            p: 101,
+            n: 53,
+            generator: (4, 33),
+        };
+        assert!(is_in_subgroup_native(params.generator, &params));
+        assert!(is_in_subgroup_native((11, 42), &params));
+        // on the curve, of order 106 and 2
+        for point in [(2, 25), (69, 0)] {
+            assert!(is_on_curve_native(point, &params));
+            assert!(!is_in_subgroup_native(point, &params));
+        }
+    }
+
+    #[test]
+    fn correct_is_on_curve() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let params = testcurve::PARAMS;
+
+        for ((x, y), expected) in [((2, 52), 1), ((2, 53), 0), ((3, 52), 0)] {
+            let res = is_on_curve::<NUM_BLOCK, _>(
+                &client_key.encrypt_radix(x, NUM_BLOCK),
+                &client_key.encrypt_radix(y, NUM_BLOCK),
+                &params,
+                &server_key,
+            );
+            assert_eq!(client_key.decrypt_radix::<u8>(&res), expected);
+        }
+    }
+}
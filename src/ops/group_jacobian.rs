@@ -14,9 +14,10 @@ use tfhe::{
 };
 
 use crate::{
-    helper::{format, read_client_key},
+    helper::{format, read_client_key, to_naf},
     numeral::Numeral,
     ops::{
+        curve::{AffinePoint, CurveParams},
         native::{add_mod_native, double_mod_native, mul_mod_native, sub_mod_native},
         primitive::parallel_fn,
         selector_zero, selector_zero_constant,
@@ -26,7 +27,7 @@ use crate::{
 use super::{
     add_mod, double_mod, inverse_mod, mul_mod,
     native::{inverse_mod_native, square_mod_native},
-    selector, square_mod, sub_mod,
+    neg_mod, selector, square_mod, sub_mod,
 };
 
 /// native double group element using jacobian coordinates.
@@ -60,6 +61,135 @@ pub fn group_projective_double_native<P: Numeral>(x: P, y: P, z: P, p: P) -> (P,
     (x_prime, y_prime, z_prime)
 }
 
+/// native add 2 group elements in affine coordinates on y^2 = x^3 + a*x + b.
+/// `(0, 0)` stands for the identity as in `precompute_generator_table`.
+pub fn group_affine_add_native<P: Numeral>(point: (P, P), other: (P, P), a: P, p: P) -> (P, P) {
+    let identity = (P::ZERO, P::ZERO);
+    if point == identity {
+        return other;
+    }
+    if other == identity {
+        return point;
+    }
+    let lambda = if point == other {
+        if point.1 == P::ZERO {
+            return identity;
+        }
+        // lambda = (3*x^2 + a) / 2*y
+        let xx = square_mod_native(point.0, p);
+        let numerator = add_mod_native(add_mod_native(double_mod_native(xx, p), xx, p), a, p);
+        mul_mod_native(
+            numerator,
+            inverse_mod_native(double_mod_native(point.1, p), p),
+            p,
+        )
+    } else {
+        if point.0 == other.0 {
+            return identity;
+        }
+        // lambda = (y2 - y1) / (x2 - x1)
+        mul_mod_native(
+            sub_mod_native(other.1, point.1, p),
+            inverse_mod_native(sub_mod_native(other.0, point.0, p), p),
+            p,
+        )
+    };
+    // x3 = lambda^2 - x1 - x2
+    let x3 = sub_mod_native(
+        sub_mod_native(square_mod_native(lambda, p), point.0, p),
+        other.0,
+        p,
+    );
+    // y3 = lambda*(x1 - x3) - y1
+    let y3 = sub_mod_native(
+        mul_mod_native(lambda, sub_mod_native(point.0, x3, p), p),
+        point.1,
+        p,
+    );
+
+    (x3, y3)
+}
+
+/// native double and add scalar mul in affine coordinates on curve `params`
+/// `(0, 0)` stands for the identity like in `group_affine_add_native`
+pub fn scalar_mul_native<P: Numeral>(scalar: P, point: (P, P), params: &CurveParams<P>) -> (P, P) {
+    let mut res = (P::ZERO, P::ZERO);
+    let mut tmp = point;
+    let mut scalar = scalar;
+    while scalar > P::ZERO {
+        if scalar.bitand(P::ONE) == P::ONE {
+            res = group_affine_add_native(res, tmp, params.a, params.p);
+        }
+        tmp = group_affine_add_native(tmp, tmp, params.a, params.p);
+        scalar >>= 1;
+    }
+
+    res
+}
+
+/// accumulator of the double and add scalar mul after each of the `P::BITS` bits of `scalar`
+/// from the least significant one, entry `i` is (scalar % 2^(i+1)) * `point`.
+/// the same as the encrypted `scalar_mul` over `i + 1` bits, for debugging it step by step.
+pub fn scalar_mul_native_trace<P: Numeral>(
+    scalar: P,
+    point: (P, P),
+    params: &CurveParams<P>,
+) -> Vec<AffinePoint<P>> {
+    let mut trace = Vec::with_capacity(<P as Numeric>::BITS);
+    let mut res = (P::ZERO, P::ZERO);
+    let mut tmp = point;
+    let mut scalar = scalar;
+    for _ in 0..<P as Numeric>::BITS {
+        if scalar.bitand(P::ONE) == P::ONE {
+            res = group_affine_add_native(res, tmp, params.a, params.p);
+        }
+        trace.push(res.into());
+        tmp = group_affine_add_native(tmp, tmp, params.a, params.p);
+        scalar >>= 1;
+    }
+
+    trace
+}
+
+/// native `u1 * g + u2 * q` in affine coordinates with Shamir's trick on curve `params`.
+/// both scalars share one doubling per bit and add `g`, `q` or the precomputed `g + q`
+/// depending on the bit pair, about half the doublings of 2 separate scalar muls.
+pub fn shamir_mul_native<P: Numeral>(
+    u1: P,
+    u2: P,
+    g: (P, P),
+    q: (P, P),
+    params: &CurveParams<P>,
+) -> (P, P) {
+    let to_bits = |mut scalar: P| {
+        let mut bits = vec![];
+        while scalar > P::ZERO {
+            bits.push(scalar.bitand(P::ONE) == P::ONE);
+            scalar >>= 1;
+        }
+        bits
+    };
+    let (bits1, bits2) = (to_bits(u1), to_bits(u2));
+    let gq = group_affine_add_native(g, q, params.a, params.p);
+
+    let mut res = (P::ZERO, P::ZERO);
+    for i in (0..bits1.len().max(bits2.len())).rev() {
+        res = group_affine_add_native(res, res, params.a, params.p);
+        let to_add = match (
+            bits1.get(i).copied().unwrap_or(false),
+            bits2.get(i).copied().unwrap_or(false),
+        ) {
+            (true, true) => gq,
+            (true, false) => g,
+            (false, true) => q,
+            (false, false) => continue,
+        };
+        res = group_affine_add_native(res, to_add, params.a, params.p);
+    }
+
+    res
+}
+
 /// native add 2 group elements using between jacobian and affine coordinates
 /// this algorithm faster than adding 2 group elements using jacobian coordinates
 pub fn group_projective_add_affine_native<P: Numeral>(
@@ -83,6 +213,9 @@ pub fn group_projective_add_affine_native<P: Numeral>(
     if x == u2 && y == s2 {
         return group_projective_double_native(x, y, z, p);
     }
+    if x == u2 {
+        return (P::ZERO, P::ZERO, P::ZERO);
+    }
 
     // h = u2 - x1
     let h = sub_mod_native(u2, x, p);
@@ -148,6 +281,9 @@ pub fn group_projective_add_projective_native<P: Numeral>(
     if u0 == u1 && s0 == s1 {
         return group_projective_double_native(x, y, z, p);
     }
+    if u0 == u1 {
+        return (P::ZERO, P::ZERO, P::ZERO);
+    }
 
     // h = u1 - u0
     let h = sub_mod_native(u1, u0, p);
@@ -186,7 +322,8 @@ pub fn group_projective_add_projective_native<P: Numeral>(
 }
 
 /// homomorphic add 2 group elements between jacobian coordinates and affine coordinates
-/// this algorithm faster than adding 2 group elements using jacobian coordinates
+/// this algorithm faster than adding 2 group elements using jacobian coordinates.
+/// the points must differ and not be inverses, see `group_projective_add_affine_complete`
 #[allow(clippy::too_many_arguments)]
 #[time("info", "Group Projective Add Mixed")]
 pub fn group_projective_add_affine<const NB: usize, P: Numeral>(
@@ -198,6 +335,58 @@ pub fn group_projective_add_affine<const NB: usize, P: Numeral>(
     other_flag_bit: &RadixCiphertext,
     p: P,
     server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    group_projective_add_affine_inner::<NB, _>(
+        x,
+        y,
+        z,
+        other_x,
+        other_y,
+        other_flag_bit,
+        false,
+        p,
+        server_key,
+    )
+}
+
+/// `group_projective_add_affine` that also returns 2P for P + P and the identity for P + -P,
+/// at the cost of a doubling per addition. for sums whose operands may coincide
+#[allow(clippy::too_many_arguments)]
+#[time("info", "Group Projective Add Mixed Complete")]
+pub fn group_projective_add_affine_complete<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    y: &RadixCiphertext,
+    z: &RadixCiphertext,
+    other_x: &RadixCiphertext,
+    other_y: &RadixCiphertext,
+    other_flag_bit: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    group_projective_add_affine_inner::<NB, _>(
+        x,
+        y,
+        z,
+        other_x,
+        other_y,
+        other_flag_bit,
+        true,
+        p,
+        server_key,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn group_projective_add_affine_inner<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    y: &RadixCiphertext,
+    z: &RadixCiphertext,
+    other_x: &RadixCiphertext,
+    other_y: &RadixCiphertext,
+    other_flag_bit: &RadixCiphertext,
+    complete: bool,
+    p: P,
+    server_key: &ServerKey,
 ) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
     // z1z1 = z1^2
     let z1z1 = square_mod::<NB, _>(z, p, server_key);
@@ -258,6 +447,17 @@ pub fn group_projective_add_affine<const NB: usize, P: Numeral>(
         p,
         server_key,
     );
+    let (x3, y3, z3) = match complete {
+        true => group_projective_add_exceptional::<NB, _>(
+            (x3, y3, z3),
+            (x, y, z),
+            &h,
+            &r,
+            p,
+            server_key,
+        ),
+        false => (x3, y3, z3),
+    };
 
     // z1'/z0' 0  1
     //    0    x' x1
@@ -334,6 +534,49 @@ pub fn group_projective_add_affine<const NB: usize, P: Numeral>(
     (x_prime, y_prime, z_prime)
 }
 
+/// fix up the result of adding 2 non identity group elements `P + Q` in jacobian coordinates
+/// where `h = u1 - u0` and `r = 2*(s1 - s0)` are 0 in the exceptional cases.
+/// h = 0, r = 0 -> P == Q, the result is the doubling of `point` i.e. P
+/// h = 0, r != 0 -> P == -Q, the result is the identity (0, 0, 0)
+fn group_projective_add_exceptional<const NB: usize, P: Numeral>(
+    sum: (RadixCiphertext, RadixCiphertext, RadixCiphertext),
+    point: (&RadixCiphertext, &RadixCiphertext, &RadixCiphertext),
+    h: &RadixCiphertext,
+    r: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    let ((x_double, y_double, z_double), (is_h_zero, is_r_zero)) = rayon::join(
+        || group_projective_double::<NB, _>(point.0, point.1, point.2, p, server_key),
+        || {
+            rayon::join(
+                || server_key.scalar_eq_parallelized(h, 0),
+                || server_key.scalar_eq_parallelized(r, 0),
+            )
+        },
+    );
+    let mut radix_is_h_zero: RadixCiphertext = is_h_zero.into_radix(NB - 1, server_key);
+    let mut radix_is_r_zero: RadixCiphertext = is_r_zero.into_radix(NB - 1, server_key);
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_h_zero, NB - 1);
+    server_key.trim_radix_blocks_msb_assign(&mut radix_is_r_zero, NB - 1);
+
+    // is_double ? double : (is_add ? sum : 0)
+    let is_double = server_key.bitand_parallelized(&radix_is_h_zero, &radix_is_r_zero);
+    let is_add =
+        server_key.sub_parallelized(&server_key.create_trivial_radix(1, 1), &radix_is_h_zero);
+    let (x, (y, z)) = rayon::join(
+        || selector(&x_double, &sum.0, &is_double, &is_add, server_key),
+        || {
+            rayon::join(
+                || selector(&y_double, &sum.1, &is_double, &is_add, server_key),
+                || selector(&z_double, &sum.2, &is_double, &is_add, server_key),
+            )
+        },
+    );
+
+    (x, y, z)
+}
+
 /// homomorphic group elements double for jacobian coordinates
 #[time("info", "Group Projective Double")]
 pub fn group_projective_double<const NB: usize, P: Numeral>(
@@ -393,7 +636,8 @@ pub fn group_projective_double<const NB: usize, P: Numeral>(
     (x_prime, y_prime, z_prime)
 }
 
-/// homomorphic group elements add between jacobian coordinates
+/// homomorphic group elements add between jacobian coordinates.
+/// the points must differ and not be inverses, see `group_projective_add_projective_complete`
 #[time("info", "Group Projective Add")]
 #[allow(clippy::too_many_arguments)]
 pub fn group_projective_add_projective<const NB: usize, P: Numeral>(
@@ -405,6 +649,38 @@ pub fn group_projective_add_projective<const NB: usize, P: Numeral>(
     z1: &RadixCiphertext,
     p: P,
     server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    group_projective_add_projective_inner::<NB, _>(x0, y0, z0, x1, y1, z1, false, p, server_key)
+}
+
+/// `group_projective_add_projective` that also returns 2P for P + P and the identity for
+/// P + -P, at the cost of a doubling per addition. for sums whose operands may coincide
+#[time("info", "Group Projective Add Complete")]
+#[allow(clippy::too_many_arguments)]
+pub fn group_projective_add_projective_complete<const NB: usize, P: Numeral>(
+    x0: &RadixCiphertext,
+    y0: &RadixCiphertext,
+    z0: &RadixCiphertext,
+    x1: &RadixCiphertext,
+    y1: &RadixCiphertext,
+    z1: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    group_projective_add_projective_inner::<NB, _>(x0, y0, z0, x1, y1, z1, true, p, server_key)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn group_projective_add_projective_inner<const NB: usize, P: Numeral>(
+    x0: &RadixCiphertext,
+    y0: &RadixCiphertext,
+    z0: &RadixCiphertext,
+    x1: &RadixCiphertext,
+    y1: &RadixCiphertext,
+    z1: &RadixCiphertext,
+    complete: bool,
+    p: P,
+    server_key: &ServerKey,
 ) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
     // z0z0 = z0^2
     // z1z1 = z1^2
@@ -499,6 +775,17 @@ pub fn group_projective_add_projective<const NB: usize, P: Numeral>(
         },
     );
 
+    if complete {
+        (x_prime, y_prime, z_prime) = group_projective_add_exceptional::<NB, _>(
+            (x_prime, y_prime, z_prime),
+            (x0, y0, z0),
+            &h,
+            &r,
+            p,
+            server_key,
+        );
+    }
+
     // z1'/z0' 0  1
     //    0    x' x1
     //    1    x0 x0
@@ -574,6 +861,42 @@ pub fn group_projective_add_projective<const NB: usize, P: Numeral>(
     (x_prime, y_prime, z_prime)
 }
 
+/// native group element negation for jacobian coordinates
+/// -(x, y, z) = (x, -y, z)
+pub fn group_projective_neg_native<P: Numeral>(x: P, y: P, z: P, p: P) -> (P, P, P) {
+    (x, sub_mod_native(P::ZERO, y, p), z)
+}
+
+/// homomorphic group element negation for jacobian coordinates
+/// -(x, y, z) = (x, -y, z), the identity (0, 0, 0) is kept as is since -0 = 0 mod p
+pub fn group_projective_neg<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    y: &RadixCiphertext,
+    z: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    let neg_y = neg_mod::<NB, _>(y, p, server_key);
+    (x.clone(), neg_y, z.clone())
+}
+
+/// homomorphic group elements subtraction between jacobian coordinates
+/// (x0, y0, z0) - (x1, y1, z1) = (x0, y0, z0) + (x1, -y1, z1)
+#[allow(clippy::too_many_arguments)]
+pub fn group_projective_sub_projective<const NB: usize, P: Numeral>(
+    x0: &RadixCiphertext,
+    y0: &RadixCiphertext,
+    z0: &RadixCiphertext,
+    x1: &RadixCiphertext,
+    y1: &RadixCiphertext,
+    z1: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    let (x1, y1, z1) = group_projective_neg::<NB, _>(x1, y1, z1, p, server_key);
+    group_projective_add_projective::<NB, _>(x0, y0, z0, &x1, &y1, &z1, p, server_key)
+}
+
 /// homomorphic scalar mul for group elements in jacobian coordinates
 pub fn group_projective_scalar_mul<const NB: usize, P: Numeral>(
     x: &RadixCiphertext,
@@ -627,6 +950,26 @@ pub fn group_projective_scalar_mul_constant<const NB: usize, P: Numeral>(
     scalar: &RadixCiphertext,
     p: P,
     server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    group_projective_scalar_mul_constant_bits::<NB, _>(
+        x,
+        y,
+        scalar,
+        <P as Numeric>::BITS,
+        p,
+        server_key,
+    )
+}
+
+/// homomorphic scalar mul for constant group element like `group_projective_scalar_mul_constant`
+/// over the `bits` least significant bits of the scalar only, the scalar must be less than 2^bits.
+pub fn group_projective_scalar_mul_constant_bits<const NB: usize, P: Numeral>(
+    x: P,
+    y: P,
+    scalar: &RadixCiphertext,
+    bits: usize,
+    p: P,
+    server_key: &ServerKey,
 ) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
     let mut tmp_x = x;
     let mut tmp_y = y;
@@ -635,7 +978,7 @@ pub fn group_projective_scalar_mul_constant<const NB: usize, P: Numeral>(
     let mut res_y = server_key.create_trivial_radix(0, NB);
     let mut res_z = server_key.create_trivial_radix(0, NB);
 
-    for _i in 0..<P as Numeric>::BITS {
+    for _i in 0..bits {
         let (mut bit, new_scalar) = rayon::join(
             || server_key.scalar_bitand_parallelized(&scalar, 1),
             || server_key.scalar_right_shift_parallelized(&scalar, 1),
@@ -662,6 +1005,40 @@ pub fn group_projective_scalar_mul_constant<const NB: usize, P: Numeral>(
     (res_x, res_y, res_z)
 }
 
+/// homomorphic scalar mul of constant affine group element `point` on a curve with a = 0
+/// by encrypted `scalar` < 2^bits, returns encrypted affine coordinates.
+/// `bits` can be lowered when the scalar is known to be small.
+/// W = 1 is the bit serial double and add, W > 1 takes W bits at a time from a table of
+/// the multiples of `point`: ceil(bits / W) group additions instead of bits, but 2^W - 1
+/// points to select with the encrypted window bits per addition.
+pub fn scalar_mul<const W: usize, const NB: usize, P: Numeral>(
+    scalar: &RadixCiphertext,
+    point: AffinePoint<P>,
+    bits: usize,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    assert!(W > 0, "window width must be at least 1");
+    assert!(!point.is_identity(), "point must not be the identity");
+    let (x, y, z) = match W == 1 {
+        true => group_projective_scalar_mul_constant_bits::<NB, _>(
+            point.x(),
+            point.y(),
+            scalar,
+            bits,
+            p,
+            server_key,
+        ),
+        false => {
+            let table = precompute_generator_table::<W, _>(point.into(), P::ZERO, p);
+            group_projective_scalar_mul_fixed_window_bits::<W, NB, _>(
+                scalar, &table, bits, p, server_key,
+            )
+        }
+    };
+    group_projective_into_affine::<NB, _>(&x, &y, &z, p, server_key)
+}
+
 /// native scalar mul for group elements.
 pub fn group_projective_scalar_mul_native<P: Numeral>(
     x: P,
@@ -692,8 +1069,39 @@ pub fn group_projective_scalar_mul_native<P: Numeral>(
     (res_x, res_y, res_z)
 }
 
+/// native scalar mul for group elements using the non-adjacent form of the scalar.
+/// on average only a third of the digits are non-zero, negative digits add the negated point.
+pub fn group_projective_scalar_mul_naf_native<P: Numeral>(
+    x: P,
+    y: P,
+    scalar: P,
+    p: P,
+) -> (P, P, P) {
+    let neg_y = sub_mod_native(P::ZERO, y, p);
+    let mut res_x = P::ZERO;
+    let mut res_y = P::ZERO;
+    let mut res_z = P::ZERO;
+
+    for digit in to_naf(scalar).into_iter().rev() {
+        (res_x, res_y, res_z) = group_projective_double_native(res_x, res_y, res_z, p);
+        match digit {
+            1 => {
+                (res_x, res_y, res_z) =
+                    group_projective_add_affine_native(res_x, res_y, res_z, x, y, p)
+            }
+            -1 => {
+                (res_x, res_y, res_z) =
+                    group_projective_add_affine_native(res_x, res_y, res_z, x, neg_y, p)
+            }
+            _ => {}
+        }
+    }
+
+    (res_x, res_y, res_z)
+}
+
 /// homomorphic scalar mul for group elements in jacobian coordinates for constant group e.g. G
-/// W is the window size. 6 is the best window size for 256 bit on 64 cores machine.
+/// on curves with a = 0. W is the window size. 6 is the best window size for 256 bit on 64 cores machine.
 #[time("info", "Group Projective Scalar Mul Windowed")]
 pub fn group_projective_scalar_mul_constant_windowed<
     const W: usize,
@@ -706,25 +1114,119 @@ pub fn group_projective_scalar_mul_constant_windowed<
     p: P,
     server_key: &ServerKey,
 ) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
-    let mut tmp_x = x;
-    let mut tmp_y = y;
+    let table = precompute_generator_table::<W, _>((x, y), P::ZERO, p);
+    group_projective_scalar_mul_fixed_window::<W, NB, _>(scalar, &table, p, server_key)
+}
+
+/// precompute the multiples of a constant group element e.g. G for `W` bits windows
+/// window `i` holds `j * 2^(W*i) * G` for `j` in `0..2^W` at `table[i * 2^W + j]`.
+/// `(0, 0)` stands for the identity at `j = 0`. `a` is the curve coefficient of x.
+pub fn precompute_generator_table<const W: usize, P: Numeral>(
+    generator: (P, P),
+    a: P,
+    p: P,
+) -> Vec<(P, P)> {
+    let windows = (<P as Numeric>::BITS + W - 1) / W;
+    let mut table = Vec::with_capacity(windows * 2usize.pow(W as u32));
+    let mut tmp = generator;
+    for _ in 0..windows {
+        table.push((P::ZERO, P::ZERO));
+        let base = tmp;
+        for _ in 1..2usize.pow(W as u32) {
+            table.push(tmp);
+            tmp = group_affine_add_native(tmp, base, a, p);
+        }
+    }
+
+    table
+}
+
+/// homomorphic scalar mul for group elements in jacobian coordinates
+/// with a precomputed table from `precompute_generator_table`
+/// W is the window size and must match the table.
+#[time("info", "Group Projective Scalar Mul Fixed Window")]
+pub fn group_projective_scalar_mul_fixed_window<const W: usize, const NB: usize, P: Numeral>(
+    scalar: &RadixCiphertext,
+    table: &[(P, P)],
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    group_projective_scalar_mul_fixed_window_bits::<W, NB, _>(
+        scalar,
+        table,
+        <P as Numeric>::BITS,
+        p,
+        server_key,
+    )
+}
+
+/// homomorphic scalar mul with a precomputed table like `group_projective_scalar_mul_fixed_window`
+/// over the `bits` least significant bits of the scalar only, the scalar must be less than 2^bits.
+pub fn group_projective_scalar_mul_fixed_window_bits<
+    const W: usize,
+    const NB: usize,
+    P: Numeral,
+>(
+    scalar: &RadixCiphertext,
+    table: &[(P, P)],
+    bits: usize,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    let identity = (
+        server_key.create_trivial_radix(0, NB),
+        server_key.create_trivial_radix(0, NB),
+        server_key.create_trivial_radix(0, NB),
+    );
+    group_projective_scalar_mul_fixed_window_from::<W, NB, _, _>(
+        identity,
+        scalar,
+        0,
+        table,
+        bits,
+        p,
+        server_key,
+        |_, _, _| {},
+    )
+}
+
+/// homomorphic scalar mul like `group_projective_scalar_mul_fixed_window_bits` resumed at bit
+/// `start`, a multiple of W, from the jacobian accumulator `acc` where `scalar` holds the bits
+/// of the scalar from `start` up. `on_step` gets the accumulator, the remaining scalar and the
+/// next bit after each window, enough to resume from there.
+#[allow(clippy::too_many_arguments)]
+pub fn group_projective_scalar_mul_fixed_window_from<
+    const W: usize,
+    const NB: usize,
+    P: Numeral,
+    F: FnMut(&(RadixCiphertext, RadixCiphertext, RadixCiphertext), &RadixCiphertext, usize),
+>(
+    acc: (RadixCiphertext, RadixCiphertext, RadixCiphertext),
+    scalar: &RadixCiphertext,
+    start: usize,
+    table: &[(P, P)],
+    bits: usize,
+    p: P,
+    server_key: &ServerKey,
+    mut on_step: F,
+) -> (RadixCiphertext, RadixCiphertext, RadixCiphertext) {
+    assert_eq!(start % W, 0, "start must be at a window boundary");
     let mut scalar = scalar.clone();
-    let mut res_x = server_key.create_trivial_radix(0, NB);
-    let mut res_y = server_key.create_trivial_radix(0, NB);
-    let mut res_z = server_key.create_trivial_radix(0, NB);
+    let mut res = acc;
 
     // take W bits at a time
     // for each bit, we have a precomputed points of 2^W - 1 points
     // take the bit, and use it to select the point
     // add the point to the result
-    // then double the temporary point W times
-    let mut i = 0;
-    while i < <P as Numeric>::BITS {
-        let chunk_size = match i + W > <P as Numeric>::BITS {
-            true => <P as Numeric>::BITS - i,
+    let bits = bits.min(<P as Numeric>::BITS);
+    let mut i = start;
+    while i < bits {
+        let chunk_size = match i + W > bits {
+            true => bits - i,
             false => W,
         };
         let _ic = i..i + chunk_size;
+        let window = i / W;
         i += chunk_size;
 
         let _tmr = stimer!(Level::Info; "Scalar Mul", "Bits {:?}", _ic);
@@ -759,17 +1261,8 @@ pub fn group_projective_scalar_mul_constant_windowed<
         drop(cal_bits_tmr);
 
         // get the precomputed values
-        let mut points = vec![(P::ZERO, P::ZERO)];
-        let tmp = (tmp_x, tmp_y);
-        for _ in 1..2usize.pow(chunk_size as u32) {
-            points.push((tmp_x, tmp_y));
-            // points are stored in tmp
-            (tmp_x, tmp_y) = {
-                let (tmp_x_new, temp_y_new, temp_z_new) =
-                    group_projective_add_affine_native(tmp_x, tmp_y, P::ONE, tmp.0, tmp.1, p);
-                group_projective_into_affine_native(tmp_x_new, temp_y_new, temp_z_new, p)
-            };
-        }
+        let offset = window * 2usize.pow(W as u32);
+        let points = table[offset..offset + 2usize.pow(chunk_size as u32)].to_vec();
 
         // select the points
         let sel_tmr = timer!(Level::Debug; "Selecting points", "Points {}", points.len() - 1);
@@ -814,19 +1307,20 @@ pub fn group_projective_scalar_mul_constant_windowed<
         drop(kary_or_tmr);
 
         // add the point
-        (res_x, res_y, res_z) = group_projective_add_affine::<NB, _>(
-            &res_x,
-            &res_y,
-            &res_z,
+        res = group_projective_add_affine::<NB, _>(
+            &res.0,
+            &res.1,
+            &res.2,
             &selected_point.0,
             &selected_point.1,
             &all_not_zero,
             p,
             server_key,
         );
+        on_step(&res, &scalar, i);
     }
 
-    (res_x, res_y, res_z)
+    res
 }
 
 /// homomorphic conversion from jacobian coordinates to affine coordinates
@@ -877,22 +1371,37 @@ pub fn group_projective_into_affine_native<P: Numeral>(x: P, y: P, z: P, p: P) -
 #[cfg(test)]
 mod tests {
 
+    use std::time::Instant;
+
+    use rand::{rngs::OsRng, Rng};
     use tfhe::{
-        integer::{keycache::IntegerKeyCache, IntegerKeyKind},
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind, RadixCiphertext, U256},
         shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
     };
 
     use crate::{
+        helper::{to_naf, u256_from_hex_string},
+        ops::curve::{AffinePoint, CurveParams},
         ops::group_jacobian::{
-            group_projective_add_affine, group_projective_add_affine_native,
+            group_projective_add_affine, group_projective_add_affine_complete,
+            group_projective_add_affine_native, group_projective_add_projective,
+            group_projective_add_projective_complete, group_projective_add_projective_native,
             group_projective_double, group_projective_double_native, group_projective_into_affine,
-            group_projective_into_affine_native,
+            group_projective_into_affine_native, group_projective_neg, group_projective_neg_native,
+            group_projective_sub_projective,
         },
+        ops::secp256k1::prelude::PARAMS,
+        ops::testcurve,
+        stats::{ProtocolOps, ProtocolStats},
         WINDOW,
     };
 
     use super::{
-        group_projective_scalar_mul_constant_windowed, group_projective_scalar_mul_native,
+        group_affine_add_native, group_projective_scalar_mul, group_projective_scalar_mul_constant,
+        group_projective_scalar_mul_constant_windowed, group_projective_scalar_mul_fixed_window,
+        group_projective_scalar_mul_naf_native, group_projective_scalar_mul_native,
+        precompute_generator_table, scalar_mul, scalar_mul_native, scalar_mul_native_trace,
+        shamir_mul_native,
     };
 
     #[test]
@@ -968,6 +1477,172 @@ mod tests {
         assert_eq!(z_dec, res.2);
     }
 
+    #[test]
+    fn correct_jacobian_add_projective_complete() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        const NUM_BLOCK: usize = 4;
+        type Integer = u8;
+        let p: Integer = 251;
+        let encrypt = |(x, y, z): (Integer, Integer, Integer)| {
+            (
+                client_key.encrypt_radix(x, NUM_BLOCK),
+                client_key.encrypt_radix(y, NUM_BLOCK),
+                client_key.encrypt_radix(z, NUM_BLOCK),
+            )
+        };
+        let add = |a: (Integer, Integer, Integer), b: (Integer, Integer, Integer)| {
+            let ((x0, y0, z0), (x1, y1, z1)) = (encrypt(a), encrypt(b));
+            let (x, y, z) = group_projective_add_projective_complete::<NUM_BLOCK, _>(
+                &x0,
+                &y0,
+                &z0,
+                &x1,
+                &y1,
+                &z1,
+                p,
+                &server_key,
+            );
+            (
+                client_key.decrypt_radix::<Integer>(&x),
+                client_key.decrypt_radix::<Integer>(&y),
+                client_key.decrypt_radix::<Integer>(&z),
+            )
+        };
+        let to_affine = |(x, y, z): (Integer, Integer, Integer)| {
+            group_projective_into_affine_native(x, y, z, p)
+        };
+
+        // P = (8, 45), 2P = (157, 22)
+        let point = (8, 45, 1);
+        let double = (157, 22, 1);
+        let identity = (0, 0, 0);
+
+        // O + P = P + O = P
+        assert_eq!(add(identity, point), point);
+        assert_eq!(add(point, identity), point);
+        // P + P = 2P
+        assert_eq!(to_affine(add(point, point)), (157, 22));
+        // P + -P = O
+        assert_eq!(
+            add(point, group_projective_neg_native(8, 45, 1, p)),
+            identity
+        );
+        // P + 2P = 3P
+        let expected = group_projective_add_projective_native(8, 45, 1, 157, 22, 1, p);
+        assert_eq!(to_affine(add(point, double)), to_affine(expected));
+    }
+
+    #[test]
+    fn correct_jacobian_add_affine_complete() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        const NUM_BLOCK: usize = 4;
+        type Integer = u8;
+        let p: Integer = 251;
+        let one = client_key.encrypt_radix(1u8, NUM_BLOCK);
+        let ct_x = client_key.encrypt_radix(8u8, NUM_BLOCK);
+        let ct_y = client_key.encrypt_radix(45u8, NUM_BLOCK);
+
+        // P + P = 2P, P + -P = O
+        for (other_y, expected) in [(45u8, (157, 22)), (206, (0, 0))] {
+            let (x_new, y_new, z_new) = group_projective_add_affine_complete::<NUM_BLOCK, _>(
+                &ct_x,
+                &ct_y,
+                &one,
+                &ct_x,
+                &client_key.encrypt_radix(other_y, NUM_BLOCK),
+                &one,
+                p,
+                &server_key,
+            );
+            let res = (
+                client_key.decrypt_radix::<Integer>(&x_new),
+                client_key.decrypt_radix::<Integer>(&y_new),
+                client_key.decrypt_radix::<Integer>(&z_new),
+            );
+            assert_eq!(
+                res,
+                group_projective_add_affine_native(8, 45, 1, 8, other_y, p)
+            );
+            assert_eq!(
+                group_projective_into_affine_native(res.0, res.1, res.2, p),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn correct_jacobian_sub_projective() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        const NUM_BLOCK: usize = 4;
+        type Integer = u8;
+        let p: Integer = 251;
+        // P = (8, 45) and Q = 2P = (157, 22)
+        let (x1, y1): (Integer, Integer) = (8, 45);
+        let (x2, y2): (Integer, Integer) = (157, 22);
+        let one = server_key.create_trivial_radix(1, NUM_BLOCK);
+
+        let ct_x1 = client_key.encrypt_radix(x1, NUM_BLOCK);
+        let ct_y1 = client_key.encrypt_radix(y1, NUM_BLOCK);
+        let ct_x2 = client_key.encrypt_radix(x2, NUM_BLOCK);
+        let ct_y2 = client_key.encrypt_radix(y2, NUM_BLOCK);
+
+        let (x_neg, y_neg, z_neg) =
+            group_projective_neg::<NUM_BLOCK, _>(&ct_x1, &ct_y1, &one, p, &server_key);
+        assert_eq!(
+            (
+                client_key.decrypt_radix::<Integer>(&x_neg),
+                client_key.decrypt_radix::<Integer>(&y_neg),
+                client_key.decrypt_radix::<Integer>(&z_neg),
+            ),
+            group_projective_neg_native(x1, y1, 1, p)
+        );
+
+        // P - P = O
+        let (_, _, z_new) = group_projective_sub_projective::<NUM_BLOCK, _>(
+            &ct_x1,
+            &ct_y1,
+            &one,
+            &ct_x1,
+            &ct_y1,
+            &one,
+            p,
+            &server_key,
+        );
+        assert_eq!(client_key.decrypt_radix::<Integer>(&z_new), 0);
+
+        // P - Q + Q = P
+        let (x_new, y_new, z_new) = group_projective_sub_projective::<NUM_BLOCK, _>(
+            &ct_x1,
+            &ct_y1,
+            &one,
+            &ct_x2,
+            &ct_y2,
+            &one,
+            p,
+            &server_key,
+        );
+        let (x_new, y_new, z_new) = group_projective_add_projective::<NUM_BLOCK, _>(
+            &x_new,
+            &y_new,
+            &z_new,
+            &ct_x2,
+            &ct_y2,
+            &one,
+            p,
+            &server_key,
+        );
+        let (x_final, y_final) =
+            group_projective_into_affine::<NUM_BLOCK, _>(&x_new, &y_new, &z_new, p, &server_key);
+        assert_eq!(client_key.decrypt_radix::<Integer>(&x_final), x1);
+        assert_eq!(client_key.decrypt_radix::<Integer>(&y_final), y1);
+    }
+
     #[test]
     fn correct_jacobian_scalar_mul() {
         let (client_key, server_key) =
@@ -999,6 +1674,185 @@ mod tests {
         assert_eq!(y_dec, res.1);
     }
 
+    #[test]
+    fn correct_jacobian_scalar_mul_fixed_window() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        const NUM_BLOCK: usize = 4;
+        const W: usize = 3;
+        type Integer = u8;
+        let p: u8 = 251;
+        let x: u8 = 8;
+        let y: u8 = 45;
+        let scalar: u8 = 26;
+        let ct_scalar = client_key.encrypt_radix(scalar, NUM_BLOCK);
+        let table = precompute_generator_table::<W, _>((x, y), 0, p);
+        assert_eq!(table.len(), 3 * 2usize.pow(W as u32));
+
+        let now = Instant::now();
+        let (x_new, y_new, z_new) = group_projective_scalar_mul_fixed_window::<W, NUM_BLOCK, _>(
+            &ct_scalar,
+            &table,
+            p,
+            &server_key,
+        );
+        println!(
+            "fixed window scalar mul done in {:.2}s",
+            now.elapsed().as_secs_f64()
+        );
+        let (x_final, y_final) =
+            group_projective_into_affine::<NUM_BLOCK, _>(&x_new, &y_new, &z_new, p, &server_key);
+        assert_eq!(client_key.decrypt_radix::<Integer>(&x_final), 92);
+        assert_eq!(client_key.decrypt_radix::<Integer>(&y_final), 120);
+
+        let now = Instant::now();
+        let (x_new, y_new, z_new) =
+            group_projective_scalar_mul_constant::<NUM_BLOCK, _>(x, y, &ct_scalar, p, &server_key);
+        println!(
+            "bit serial scalar mul done in {:.2}s",
+            now.elapsed().as_secs_f64()
+        );
+        let (x_final, y_final) =
+            group_projective_into_affine::<NUM_BLOCK, _>(&x_new, &y_new, &z_new, p, &server_key);
+        assert_eq!(client_key.decrypt_radix::<Integer>(&x_final), 92);
+        assert_eq!(client_key.decrypt_radix::<Integer>(&y_final), 120);
+    }
+
+    #[test]
+    fn correct_scalar_mul() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        const NUM_BLOCK: usize = 4;
+        type Integer = u8;
+        let p: Integer = 251;
+        let point = AffinePoint::<Integer>::new(8, 45);
+
+        for (scalar, bits) in [(1, 8), (6, 8), (26, 8), (200, 8), (13, 4)] {
+            let ct_scalar = client_key.encrypt_radix(scalar, NUM_BLOCK);
+            let res = group_projective_scalar_mul_native(point.x(), point.y(), scalar, p);
+            let res = group_projective_into_affine_native(res.0, res.1, res.2, p);
+
+            let decrypt = |(x, y): (RadixCiphertext, RadixCiphertext)| {
+                (
+                    client_key.decrypt_radix::<Integer>(&x),
+                    client_key.decrypt_radix::<Integer>(&y),
+                )
+            };
+            for (window, enc_res) in [
+                (
+                    1,
+                    scalar_mul::<1, NUM_BLOCK, _>(&ct_scalar, point, bits, p, &server_key),
+                ),
+                (
+                    2,
+                    scalar_mul::<2, NUM_BLOCK, _>(&ct_scalar, point, bits, p, &server_key),
+                ),
+                (
+                    3,
+                    scalar_mul::<3, NUM_BLOCK, _>(&ct_scalar, point, bits, p, &server_key),
+                ),
+            ] {
+                assert_eq!(decrypt(enc_res), res, "scalar {} window {}", scalar, window);
+            }
+        }
+    }
+
+    /// counts are global, run alone so concurrent tests don't add to them:
+    /// `cargo test --release bench_scalar_mul_window -- --ignored --nocapture`
+    ///
+    /// every group addition costs the same number of homomorphic muls whatever the point,
+    /// so on 8 bits scalars W = 1..4 do 8, 4, 3 and 2 additions, i.e. the mul count drops
+    /// with W. the window selection is scalar muls by the bits and 1 block bitands only:
+    /// 2^W - 1 points per window, 8, 12, 17 and 30 points for W = 1..4. an addition is
+    /// 11 mul mods in `group_projective_add_affine`, so the op count sweet spot of the toy
+    /// curve is W = 4 with 2 table lookups.
+    #[test]
+    #[ignore]
+    fn bench_scalar_mul_window() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let params = testcurve::PARAMS;
+        let scalar = OsRng.gen_range(1..params.n);
+        let ct_scalar = client_key.encrypt_radix(scalar, NUM_BLOCK);
+        let expected = scalar_mul_native(scalar, params.generator, &params);
+
+        let count = || {
+            ProtocolStats::stats_by_op()
+                .get(&ProtocolOps::Mul.to_string())
+                .copied()
+                .unwrap_or_default()
+                .count
+        };
+        let mut muls = vec![];
+        let mut run = |window: usize, f: &dyn Fn() -> (RadixCiphertext, RadixCiphertext)| {
+            let before = count();
+            let now = Instant::now();
+            let (x, y) = f();
+            let elapsed = now.elapsed().as_secs_f64();
+            let after = count();
+            assert_eq!(
+                (
+                    client_key.decrypt_radix::<u8>(&x),
+                    client_key.decrypt_radix::<u8>(&y)
+                ),
+                expected
+            );
+            println!("W = {}: {} muls in {:.2}s", window, after - before, elapsed);
+            muls.push(after - before);
+        };
+        let (g, p) = (AffinePoint::from(params.generator), params.p);
+        run(1, &|| {
+            scalar_mul::<1, NUM_BLOCK, _>(&ct_scalar, g, 8, p, &server_key)
+        });
+        run(2, &|| {
+            scalar_mul::<2, NUM_BLOCK, _>(&ct_scalar, g, 8, p, &server_key)
+        });
+        run(3, &|| {
+            scalar_mul::<3, NUM_BLOCK, _>(&ct_scalar, g, 8, p, &server_key)
+        });
+        run(4, &|| {
+            scalar_mul::<4, NUM_BLOCK, _>(&ct_scalar, g, 8, p, &server_key)
+        });
+
+        assert!(muls.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn correct_jacobian_scalar_mul_encrypted_point() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        const NUM_BLOCK: usize = 4;
+        type Integer = u8;
+        let p: u8 = 251;
+        let x: u8 = 8;
+        let y: u8 = 45;
+        let scalar: u8 = 26;
+        let ct_x = client_key.encrypt_radix(x, NUM_BLOCK);
+        let ct_y = client_key.encrypt_radix(y, NUM_BLOCK);
+        let ct_scalar = client_key.encrypt_radix(scalar, NUM_BLOCK);
+
+        // jacobian coordinates all the way, only one conversion to affine at the end
+        let (x_new, y_new, z_new) = group_projective_scalar_mul::<NUM_BLOCK, _>(
+            &ct_x,
+            &ct_y,
+            &server_key.create_trivial_radix(1, NUM_BLOCK),
+            &ct_scalar,
+            p,
+            &server_key,
+        );
+        let (x_final, y_final) =
+            group_projective_into_affine::<NUM_BLOCK, _>(&x_new, &y_new, &z_new, p, &server_key);
+
+        let x_dec = client_key.decrypt_radix::<Integer>(&x_final);
+        let y_dec = client_key.decrypt_radix::<Integer>(&y_final);
+
+        assert_eq!((x_dec, y_dec), (92, 120));
+    }
+
     #[test]
     fn correct_native_group_ops_jacobian() {
         let p: u8 = 251;
@@ -1029,4 +1883,159 @@ mod tests {
         let affine = group_projective_into_affine_native(g.0, g.1, g.2, p);
         assert_eq!(affine, (92, 120));
     }
+
+    #[test]
+    fn correct_affine_scalar_mul_native() {
+        let params = *PARAMS;
+        let expected = [
+            (
+                "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                "0x483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            ),
+            (
+                "0xc6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+                "0x1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a",
+            ),
+            (
+                "0xf9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                "0x388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672",
+            ),
+            (
+                "0xe493dbf1c10d80f3581e4904930b1404cc6c13900ee0758474fa94abe8c4cd13",
+                "0x51ed993ea0d455b75642e2098ea51448d967ae33bfbdfe40cfe97bdc47739922",
+            ),
+        ];
+        for (i, (x, y)) in expected.into_iter().enumerate() {
+            let scalar = U256::from(i as u8 + 1);
+            assert_eq!(
+                scalar_mul_native(scalar, params.generator, &params),
+                (u256_from_hex_string(x), u256_from_hex_string(y))
+            );
+        }
+        assert_eq!(
+            scalar_mul_native(U256::ZERO, params.generator, &params),
+            (U256::ZERO, U256::ZERO)
+        );
+        assert_eq!(
+            scalar_mul_native(params.n, params.generator, &params),
+            (U256::ZERO, U256::ZERO)
+        );
+
+        // toy curve y^2 = x^3 + 7 % 251 where (8, 45) has order 36
+        let params = CurveParams {
+            a: 0u8,
+            b: 7,
+            p: 251,
+            n: 36,
+            generator: (8, 45),
+        };
+        assert_eq!(scalar_mul_native(2, params.generator, &params), (157, 22));
+        assert_eq!(scalar_mul_native(6, params.generator, &params), (176, 125));
+        assert_eq!(scalar_mul_native(26, params.generator, &params), (92, 120));
+        assert_eq!(scalar_mul_native(36, params.generator, &params), (0, 0));
+        assert_eq!(scalar_mul_native(37, params.generator, &params), (8, 45));
+    }
+
+    #[test]
+    fn correct_scalar_mul_native_trace() {
+        let params = testcurve::PARAMS;
+        for k in 0..params.n {
+            let trace = scalar_mul_native_trace(k, params.generator, &params);
+            assert_eq!(trace.len(), 8);
+            for (i, step) in trace.iter().enumerate() {
+                let partial = (k as u16 % (1 << (i + 1))) as u8;
+                assert_eq!(
+                    <(u8, u8)>::from(*step),
+                    scalar_mul_native(partial, params.generator, &params)
+                );
+            }
+            assert_eq!(
+                trace.last().copied(),
+                Some(scalar_mul_native(k, params.generator, &params).into())
+            );
+        }
+        // 10 = 0b1010, the accumulator only changes on set bits
+        let trace = scalar_mul_native_trace(10, params.generator, &params);
+        assert!(trace[0].is_identity());
+        assert_eq!(trace[1], AffinePoint::new(21, 107));
+        assert_eq!(trace[2], trace[1]);
+        assert_eq!(trace[3], AffinePoint::new(33, 122));
+    }
+
+    #[test]
+    fn correct_shamir_mul_native() {
+        let params = CurveParams {
+            a: 0u8,
+            b: 7,
+            p: 251,
+            n: 36,
+            generator: (8, 45),
+        };
+        let g = params.generator;
+        let q = scalar_mul_native(5, g, &params);
+        for u1 in 0..params.n {
+            for u2 in 0..params.n {
+                let expected = group_affine_add_native(
+                    scalar_mul_native(u1, g, &params),
+                    scalar_mul_native(u2, q, &params),
+                    params.a,
+                    params.p,
+                );
+                assert_eq!(shamir_mul_native(u1, u2, g, q, &params), expected);
+            }
+        }
+
+        let params = *PARAMS;
+        let q = scalar_mul_native(
+            u256_from_hex_string(
+                "0xc9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            ),
+            params.generator,
+            &params,
+        );
+        let u1 = u256_from_hex_string(
+            "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        );
+        let u2 = u256_from_hex_string(
+            "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140",
+        );
+        assert_eq!(
+            shamir_mul_native(u1, u2, params.generator, q, &params),
+            group_affine_add_native(
+                scalar_mul_native(u1, params.generator, &params),
+                scalar_mul_native(u2, q, &params),
+                params.a,
+                params.p,
+            )
+        );
+    }
+
+    #[test]
+    fn correct_native_scalar_mul_naf() {
+        let params = testcurve::PARAMS;
+        let p = params.p;
+        let (x, y) = params.generator;
+
+        for _ in 0..1000 {
+            let scalar: u8 = OsRng.gen();
+            let g = group_projective_scalar_mul_native(x, y, scalar, p);
+            let g_naf = group_projective_scalar_mul_naf_native(x, y, scalar, p);
+            assert_eq!(
+                group_projective_into_affine_native(g.0, g.1, g.2, p),
+                group_projective_into_affine_native(g_naf.0, g_naf.1, g_naf.2, p)
+            );
+        }
+
+        // a third of the digits are non-zero on average
+        let non_zero = (0..1000)
+            .map(|_| {
+                to_naf(OsRng.gen::<u128>())
+                    .iter()
+                    .filter(|d| **d != 0)
+                    .count()
+            })
+            .sum::<usize>();
+        let average = non_zero as f64 / 1000.;
+        assert!(average > 128. / 3. - 3. && average < 128. / 3. + 3.);
+    }
 }
@@ -0,0 +1,229 @@
+use tfhe::integer::{IntegerCiphertext, RadixCiphertext, ServerKey};
+
+use crate::stats::{ProtocolOps, ProtocolStats};
+
+/// operands of at most this many blocks are multiplied directly
+pub const KARATSUBA_THRESHOLD: usize = 16;
+
+/// homomorphic a * b of 2 NB blocks values into NB * 2 blocks without reduction
+/// with Karatsuba above `KARATSUBA_THRESHOLD` blocks, see `mul_karatsuba_with_threshold`
+pub fn mul_karatsuba<const NB: usize>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    mul_karatsuba_with_threshold::<NB>(a, b, KARATSUBA_THRESHOLD, server_key)
+}
+
+/// homomorphic a * b of 2 NB blocks values into NB * 2 blocks without reduction.
+/// a = a1 * 2^h + a0 and b = b1 * 2^h + b0 are split at half the blocks and
+/// a * b = z2 * 2^2h + (z1 - z2 - z0) * 2^h + z0 with z0 = a0 * b0, z2 = a1 * b1 and
+/// z1 = (a0 + a1) * (b0 + b1), 3 half size multiplications instead of 4.
+/// the halves are split again until they are at most `threshold` blocks, at least 3 blocks
+/// since a0 + a1 of 3 blocks is 3 blocks again.
+pub fn mul_karatsuba_with_threshold<const NB: usize>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    threshold: usize,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    assert_eq!(a.blocks().len(), NB);
+    assert_eq!(b.blocks().len(), NB);
+    karatsuba(a, b, threshold.max(3), server_key)
+}
+
+/// number of base multiplications of `mul_karatsuba_with_threshold` on `num_block` blocks
+pub fn karatsuba_base_muls(num_block: usize, threshold: usize) -> usize {
+    match num_block <= threshold.max(3) {
+        true => 1,
+        false => {
+            let low = num_block / 2;
+            let high = num_block - low;
+            karatsuba_base_muls(low, threshold)
+                + karatsuba_base_muls(high, threshold)
+                + karatsuba_base_muls(high + 1, threshold)
+        }
+    }
+}
+
+fn resize(x: &RadixCiphertext, len: usize, server_key: &ServerKey) -> RadixCiphertext {
+    let x_len = x.blocks().len();
+    match x_len > len {
+        true => server_key.trim_radix_blocks_msb(x, x_len - len),
+        false => server_key.extend_radix_with_trivial_zero_blocks_msb(x, len - x_len),
+    }
+}
+
+/// a * b of 2 values of the same number of blocks n into 2n blocks
+fn karatsuba(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    threshold: usize,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let n = a.blocks().len();
+    if n <= threshold {
+        let mut a = resize(a, 2 * n, server_key);
+        let b = resize(b, 2 * n, server_key);
+        ProtocolStats::time_op(ProtocolOps::Mul, || {
+            server_key.mul_assign_parallelized(&mut a, &b)
+        });
+        return a;
+    }
+
+    let low = n / 2;
+    let high = n - low;
+    let split = |x: &RadixCiphertext| {
+        (
+            server_key.trim_radix_blocks_msb(x, high),
+            server_key.trim_radix_blocks_lsb(x, low),
+        )
+    };
+    let ((a0, a1), (b0, b1)) = (split(a), split(b));
+    // a0 + a1 and b0 + b1 fit high + 1 blocks
+    let add = |x0: &RadixCiphertext, x1: &RadixCiphertext| {
+        let x0 = resize(x0, high + 1, server_key);
+        let x1 = resize(x1, high + 1, server_key);
+        ProtocolStats::time_op(ProtocolOps::Add, || server_key.add_parallelized(&x0, &x1))
+    };
+    let (a01, b01) = rayon::join(|| add(&a0, &a1), || add(&b0, &b1));
+
+    let (z0, (z2, z1)) = rayon::join(
+        || karatsuba(&a0, &b0, threshold, server_key),
+        || {
+            rayon::join(
+                || karatsuba(&a1, &b1, threshold, server_key),
+                || karatsuba(&a01, &b01, threshold, server_key),
+            )
+        },
+    );
+
+    // z1 - z0 - z2 = a0 * b1 + a1 * b0 < 2^(2 * high) blocks, no underflow
+    let z1 = resize(&z1, 2 * n, server_key);
+    let z0 = resize(&z0, 2 * n, server_key);
+    let z2 = resize(&z2, 2 * n, server_key);
+    let mid = ProtocolStats::time_op(ProtocolOps::Add, || {
+        server_key.sub_parallelized(&server_key.sub_parallelized(&z1, &z0), &z2)
+    });
+    // shift by whole blocks
+    let mid = resize(
+        &server_key.extend_radix_with_trivial_zero_blocks_lsb(&mid, low),
+        2 * n,
+        server_key,
+    );
+    let z2 = resize(
+        &server_key.extend_radix_with_trivial_zero_blocks_lsb(&z2, 2 * low),
+        2 * n,
+        server_key,
+    );
+    ProtocolStats::time_op(ProtocolOps::Add, || {
+        server_key.add_parallelized(&server_key.add_parallelized(&z0, &mid), &z2)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use rand::{rngs::OsRng, Rng};
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use crate::stats::{ProtocolOps, ProtocolStats};
+
+    use super::{karatsuba_base_muls, mul_karatsuba_with_threshold};
+
+    #[test]
+    fn correct_mul_karatsuba() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 8;
+
+        for (a, b) in [
+            (u16::MAX, u16::MAX),
+            (OsRng.gen(), OsRng.gen()),
+            (0, OsRng.gen()),
+        ] {
+            let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+            let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+            let builtin = server_key.mul_parallelized(
+                &server_key.extend_radix_with_trivial_zero_blocks_msb(&enc_a, NUM_BLOCK),
+                &server_key.extend_radix_with_trivial_zero_blocks_msb(&enc_b, NUM_BLOCK),
+            );
+            // 5 base muls with threshold 4 and 11 with threshold 3
+            for threshold in [3, 4] {
+                let res = mul_karatsuba_with_threshold::<NUM_BLOCK>(
+                    &enc_a,
+                    &enc_b,
+                    threshold,
+                    &server_key,
+                );
+                assert_eq!(res.blocks().len(), NUM_BLOCK * 2);
+                assert_eq!(
+                    client_key.decrypt_radix::<u32>(&res),
+                    client_key.decrypt_radix::<u32>(&builtin)
+                );
+                assert_eq!(client_key.decrypt_radix::<u32>(&res), a as u32 * b as u32);
+            }
+        }
+    }
+
+    #[test]
+    fn correct_karatsuba_base_muls() {
+        assert_eq!(karatsuba_base_muls(8, 8), 1);
+        // 8 splits into 4, 4, 5 and 5 into 2, 3, 4
+        assert_eq!(karatsuba_base_muls(8, 4), 5);
+        assert_eq!(karatsuba_base_muls(8, 3), 11);
+        assert_eq!(karatsuba_base_muls(8, 2), karatsuba_base_muls(8, 3));
+        // 128 blocks U256 down to 32 and 16 blocks
+        assert_eq!(karatsuba_base_muls(128, 32), 17);
+        assert_eq!(karatsuba_base_muls(128, 16), 55);
+    }
+
+    /// op counts of 8 blocks products, karatsuba splits 8 into 4, 4, 5 and 5 into 2, 3, 3
+    /// and each split is 2 adds of the halves, 1 for z1 - z0 - z2 and 1 to recombine.
+    /// `cargo test --release correct_mul_karatsuba_stats -- --nocapture` prints the timings
+    #[test]
+    fn correct_mul_karatsuba_stats() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 8;
+        const THRESHOLD: usize = 4;
+        let (a, b) = (OsRng.gen::<u16>(), OsRng.gen::<u16>());
+        let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+        let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+        let count = |stats: &ProtocolStats, op: ProtocolOps| {
+            stats.ops.get(&op).map_or(0, |stats| stats.count)
+        };
+
+        let now = Instant::now();
+        let (res, karatsuba) = ProtocolStats::collect(2, || {
+            mul_karatsuba_with_threshold::<NUM_BLOCK>(&enc_a, &enc_b, THRESHOLD, &server_key)
+        });
+        let karatsuba_time = now.elapsed().as_secs_f64();
+        assert_eq!(client_key.decrypt_radix::<u32>(&res), a as u32 * b as u32);
+        assert_eq!(
+            count(&karatsuba, ProtocolOps::Mul),
+            karatsuba_base_muls(NUM_BLOCK, THRESHOLD)
+        );
+        assert_eq!(count(&karatsuba, ProtocolOps::Add), 2 * 4);
+
+        let now = Instant::now();
+        let (res, schoolbook) = ProtocolStats::collect(2, || {
+            mul_karatsuba_with_threshold::<NUM_BLOCK>(&enc_a, &enc_b, NUM_BLOCK, &server_key)
+        });
+        let schoolbook_time = now.elapsed().as_secs_f64();
+        assert_eq!(client_key.decrypt_radix::<u32>(&res), a as u32 * b as u32);
+        assert_eq!(count(&schoolbook, ProtocolOps::Mul), 1);
+        assert_eq!(count(&schoolbook, ProtocolOps::Add), 0);
+        println!(
+            "karatsuba {} muls of at most {} blocks in {:.2}s, schoolbook 1 mul in {:.2}s",
+            count(&karatsuba, ProtocolOps::Mul),
+            THRESHOLD,
+            karatsuba_time,
+            schoolbook_time
+        );
+    }
+}
@@ -1,21 +1,39 @@
 #![allow(clippy::redundant_closure_call)]
 
-use std::time::Instant;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
+use lazy_static::lazy_static;
 use logging_timer::time;
 use num_bigint::BigInt;
-use rand::Rng;
-use tfhe::integer::{
-    block_decomposition::{DecomposableInto, RecomposableFrom},
-    IntegerCiphertext, RadixCiphertext, ServerKey, U512,
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use tfhe::{
+    core_crypto::prelude::Numeric,
+    integer::{
+        block_decomposition::{DecomposableInto, RecomposableFrom},
+        ClientKey, IntegerCiphertext, RadixCiphertext, ServerKey, U256, U512,
+    },
 };
 
 use crate::{
-    helper::{bigint_ilog2_ceil, bigint_to_u128, from_bigint, to_bigint},
+    helper::{
+        bigint_to_u128, bits_per_block, decrypt_bigint_wide, format, from_bigint,
+        from_bigint_unchecked, read_client_key, required_blocks, to_bigint, to_naf,
+    },
     numeral::Numeral,
+    stats::{ProtocolOps, ProtocolStats},
+    DEBUG_CHECKS,
 };
 
-use super::{modulo_div_rem, modulo_fast};
+use super::{
+    backend::FheInt, modulo_div_rem, modulo_fast_inner, native::modulo_native,
+    primitive::parallel_fn, selector_zero_constant,
+};
 
 /// Calculate n, m, p from coeff
 /// `coeff` in the form of p = 2^n_0 - 2^n_1 - ... - 2^n_{k-1} - n_k
@@ -38,17 +56,73 @@ pub fn mersenne_coeff(coeff: &[u32]) -> (u32, BigInt, BigInt, BigInt) {
     (n, p, q, c)
 }
 
-/// Calculate n, c from p
+/// Calculate n, c from p where n is the bit length of p, so 1 <= c <= 2^(n-1)
 /// `c` must be in range 0 <= c <= 2^floor(n/2)
 #[inline(always)]
 pub fn mersenne_coeff_p<P: Numeral>(p: P) -> (u32, BigInt) {
     let pb = to_bigint(p);
-    let n = bigint_ilog2_ceil(&pb);
+    assert!(pb > BigInt::from(0), "modulus must be positive");
+    let n = pb.bits() as u32;
     let c = (BigInt::from(1) << n) - &pb;
 
     (n, c)
 }
 
+/// NAF weight of c up to which `scalar_mul_sparse` shifts and adds instead of a scalar mul
+pub const SPARSE_SCALAR_MAX_WEIGHT: usize = 6;
+
+/// a * c truncated to the blocks of a like `scalar_mul_parallelized`, c < 2^128 or it panics.
+/// a c of low NAF weight like the 2^32 + 2^10 - 2^6 + 2^4 + 1 of secp256k1's field prime
+/// is the sum of a few shifted a, the shifts run in parallel and the terms are summed in a tree.
+pub fn scalar_mul_sparse(
+    a: &RadixCiphertext,
+    c: &BigInt,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let c = bigint_to_u128(c);
+    let terms = to_naf(c)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, digit)| *digit != 0)
+        .collect::<Vec<_>>();
+    if terms.is_empty() || terms.len() > SPARSE_SCALAR_MAX_WEIGHT {
+        return server_key.scalar_mul_parallelized(a, c);
+    }
+    let (pos, neg): (Vec<_>, Vec<_>) = terms
+        .into_par_iter()
+        .map(|(i, digit)| {
+            (
+                server_key.scalar_left_shift_parallelized(a, i as u64),
+                digit,
+            )
+        })
+        .partition(|(_, digit)| *digit > 0);
+    let sum = |terms: Vec<(RadixCiphertext, i8)>| {
+        let terms = terms.into_iter().map(|(term, _)| term).collect::<Vec<_>>();
+        parallel_fn(&terms, |a, b| server_key.add_parallelized(a, b))
+    };
+    // the most significant NAF digit is 1, there is always a positive term
+    match neg.is_empty() {
+        true => sum(pos),
+        false => {
+            let (pos, neg) = rayon::join(|| sum(pos), || sum(neg));
+            server_key.sub_parallelized(&pos, &neg)
+        }
+    }
+}
+
+/// a * c truncated to the blocks of a for a c too wide for `scalar_mul_sparse`, c is made a
+/// trivial ciphertext of the blocks of a and multiplied as a ciphertext
+pub fn scalar_mul_trivial(
+    a: &RadixCiphertext,
+    c: &BigInt,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let c = from_bigint::<U512>(c).expect("c fits U512");
+    let c = server_key.create_trivial_radix(c, a.blocks().len());
+    server_key.mul_parallelized(a, &c)
+}
+
 /// native x mod p^2 mod p
 /// `coeff` in the form of p = 2^n_0 - 2^n_1 - ... - 2^n_{k-1} - n_k
 pub fn mersenne_mod_native<P: Numeral>(x: P, p: P) -> P {
@@ -69,100 +143,597 @@ pub fn mersenne_mod_native<P: Numeral>(x: P, p: P) -> P {
     // x % p = (a*2^n + b) % p = c * a + b % p
     let x_mod_p = &a * &c + &b;
 
-    from_bigint(&if x_mod_p >= p { x_mod_p - p } else { x_mod_p })
+    from_bigint_unchecked(&if x_mod_p >= p { x_mod_p - p } else { x_mod_p })
+}
+
+/// native x mod p of x read as a two's complement value of `P::BITS` bits, e.g. 0xfffb as u16
+/// is -5. the result is the positive residue like `mersenne_mod_native`.
+/// expect |x| < 2^(bits - 1), every value but -2^(bits - 1), and p^2 >= 2^(bits - 1).
+pub fn mersenne_mod_signed_native<P: Numeral>(x: P, p: P) -> P {
+    let bits = <P as Numeric>::BITS as u64;
+    let p2 = to_bigint(p).pow(2);
+    assert!(p2.bits() >= bits, "p^2 must be at least 2^(bits - 1)");
+    let mut x = to_bigint(x);
+    assert!(
+        x != BigInt::from(1) << (bits - 1),
+        "|x| must be below 2^(bits - 1)"
+    );
+    if x.bit(bits - 1) {
+        // -2^(bits - 1) < x < 0 -> 0 < x + p^2 < p^2
+        x = x - (BigInt::from(1) << bits) + p2;
+    }
+    mersenne_mod_native(from_bigint_unchecked(&x), p)
+}
+
+/// `mersenne_coeff_p` of `p` computed once to reuse across many reductions by the same modulus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MersenneCtx<P: Numeral> {
+    pub p: P,
+    /// bit length of `p`
+    pub n: u32,
+    /// c = 2^n - p, the folding passes take it as a u128 scalar or as a trivial ciphertext
+    /// when it is wider
+    pub c: BigInt,
+    /// blocks of `bits_per_block` bits needed to hold `c`
+    pub c_blocks: usize,
+    pub bits_per_block: usize,
+}
+
+lazy_static! {
+    /// contexts of `MersenneCtx::cached` keyed by the type of p, p and the block size
+    static ref MERSENNE_CTX_CACHE: RwLock<HashMap<(TypeId, BigInt, usize), Arc<dyn Any + Send + Sync>>> =
+        RwLock::new(HashMap::new());
+}
+
+impl<P: Numeral> MersenneCtx<P> {
+    /// the context of p built on the first call and shared by every later reduction by p,
+    /// e.g. the thousands of `mul_mod` of a signature over the same 2 moduli
+    pub fn cached(p: P, bits_per_block: usize) -> Arc<Self> {
+        let key = (TypeId::of::<P>(), to_bigint(p), bits_per_block);
+        if let Some(ctx) = MERSENNE_CTX_CACHE.read().unwrap().get(&key) {
+            return ctx.clone().downcast().expect("keyed by the type of p");
+        }
+        MERSENNE_CTX_CACHE
+            .write()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Arc::new(MersenneCtx::new(p, bits_per_block)))
+            .clone()
+            .downcast()
+            .expect("keyed by the type of p")
+    }
+
+    pub fn new(p: P, bits_per_block: usize) -> Self {
+        let (n, c) = mersenne_coeff_p(p);
+        let c_blocks = (c.bits() as usize + bits_per_block - 1) / bits_per_block;
+        MersenneCtx {
+            p,
+            n,
+            c,
+            c_blocks,
+            bits_per_block,
+        }
+    }
 }
 
 /// homomorphic x mod p^2 mod p
-/// expect x < p^2
-#[time("trace", "Modulus Reduction Mersenne+Barrett")]
+/// expect x < p^2, x wider than NB * 2 blocks is folded down first
 pub fn mod_mersenne<const NB: usize, P: Numeral>(
     x: &RadixCiphertext,
     p: P,
     server_key: &ServerKey,
 ) -> RadixCiphertext {
-    let (n, c) = mersenne_coeff_p(p);
-    let ceilc = bigint_ilog2_ceil(&c);
+    let ctx = MersenneCtx::cached(p, bits_per_block(x));
+    mod_mersenne_ctx::<NB, _>(x, &ctx, server_key)
+}
+
+/// fold x = a * 2^n + b into c * a + b until x < 2^(2n), all the 2 passes of `mod_mersenne`
+/// need, for x wider than NB * 2 blocks. with 2 * bits(c) < n each fold divides the bound of x
+/// by at least 2^(n/2) and x shrinks to the blocks of the new bound.
+fn fold_wide<const NB: usize, T: FheInt>(x: &T, n: u32, c: &BigInt, key: &T::Key) -> T {
+    let bits_per_block = x.bits_per_block();
+    let blocks = |bound: &BigInt| (bound.bits() as usize).div_ceil(bits_per_block).max(NB);
+    let limit = BigInt::from(1) << (2 * n);
+    let mut bound = (BigInt::from(1) << (x.num_blocks() * bits_per_block)) - 1;
+    let mut x = x.clone();
+    while bound >= limit {
+        // the largest c * a + b for any value up to bound
+        bound = c * (&bound >> n) + (BigInt::from(1) << n) - 1;
+        let len = blocks(&bound);
+        let a = x.scalar_right_shift(n as u64, key);
+        let a_shifted = a.scalar_left_shift(n as u64, key);
+        let b = ProtocolStats::time_op(ProtocolOps::Add, || x.sub(&a_shifted, key));
+        let x_len = x.num_blocks();
+        let a = a.trim_msb(x_len - len, key);
+        let b = b.trim_msb(x_len - NB, key).extend_msb(len - NB, key);
+        let ca = ProtocolStats::time_op(ProtocolOps::ScalarMul, || a.scalar_mul(c, key));
+        x = ProtocolStats::time_op(ProtocolOps::Add, || ca.add(&b, key));
+    }
+
+    x
+}
+
+/// homomorphic x mod p^2 mod p with precomputed `ctx`
+/// expect x < p^2
+#[time("trace", "Modulus Reduction Mersenne+Barrett")]
+pub fn mod_mersenne_ctx<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    ctx: &MersenneCtx<P>,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let res = ProtocolStats::time_op(ProtocolOps::ModReduce, || {
+        mod_mersenne_inner::<NB, _>(x, ctx, server_key)
+    });
+    if DEBUG_CHECKS {
+        read_client_key(|client_key| check_mod(x, &res, ctx.p, client_key));
+    }
+    res
+}
+
+/// decrypt x and the reduced `res` and compare with x mod p, the `DEBUG_CHECKS` check of
+/// `mod_mersenne_ctx` when a client key is set. skipped for a client key of another block size,
+/// e.g. the global key of the tests while a test runs with 4 bits blocks.
+fn check_mod<P: Numeral>(x: &RadixCiphertext, res: &RadixCiphertext, p: P, client_key: &ClientKey) {
+    if client_key.parameters().message_modulus() != x.blocks()[0].message_modulus {
+        return;
+    }
+    let x = decrypt_bigint_wide(x, client_key);
+    let p = to_bigint(p);
+    assert_eq!(
+        decrypt_bigint_wide(res, client_key),
+        &x % &p,
+        "x mod p of x = {} p = {}",
+        x,
+        p
+    );
+}
+
+fn mod_mersenne_inner<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    ctx: &MersenneCtx<P>,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let MersenneCtx { p, n, ref c, .. } = *ctx;
+    let bits_per_block = bits_per_block(x);
+    assert_eq!(
+        bits_per_block, ctx.bits_per_block,
+        "ctx was built for another block size"
+    );
+    let ceilc = c.bits() as u32;
+    // the two passes need 2 * bits(c) < n, wider c goes through Barrett. a c over 128 bits is
+    // multiplied as a trivial ciphertext by `FheInt::scalar_mul`, the others stay scalar muls
     if ceilc >= n / 2 {
-        let k = 4 * NB;
+        // x wider than NB * 2 blocks, e.g. sums left unreduced
+        if x.blocks().len() > NB * 2 {
+            let mut r = modulo_div_rem::<NB, _>(x, p, server_key);
+            let len = r.blocks().len();
+            server_key.trim_radix_blocks_msb_assign(&mut r, len - NB);
+            return r;
+        }
+        let k = 2 * NB * bits_per_block;
         let m_bigint = BigInt::from(2).pow(k as u32) / to_bigint(p);
-        let block_to_add = (m_bigint.bits() - 2 * NB as u64 + 1) / 2;
-        let m = from_bigint::<U512>(&m_bigint);
-        let mut x =
-            server_key.extend_radix_with_trivial_zero_blocks_msb(x, NB + block_to_add as usize);
-        let mut q = server_key.scalar_mul_parallelized(&x, m);
+        let block_to_add =
+            (m_bigint.bits() as usize - NB * bits_per_block + bits_per_block - 1) / bits_per_block;
+        let m = from_bigint::<U512>(&m_bigint).expect("m < 2^(2k) fits U512");
+        let mut x = server_key.extend_radix_with_trivial_zero_blocks_msb(x, NB + block_to_add);
+        let mut q = ProtocolStats::time_op(ProtocolOps::ScalarMul, || {
+            server_key.scalar_mul_parallelized(&x, m)
+        });
         server_key.scalar_right_shift_assign_parallelized(&mut q, k as u64);
-        server_key.sub_assign_parallelized(&mut x, &server_key.scalar_mul_parallelized(&q, p));
+        let qp = ProtocolStats::time_op(ProtocolOps::ScalarMul, || {
+            server_key.scalar_mul_parallelized(&q, p)
+        });
+        ProtocolStats::time_op(ProtocolOps::Add, || {
+            server_key.sub_assign_parallelized(&mut x, &qp)
+        });
         let len = x.blocks().len();
         server_key.trim_radix_blocks_msb_assign(&mut x, len - (NB + 1));
 
-        return modulo_fast::<NB, _>(&x, p, server_key);
+        return modulo_fast_inner::<NB, _>(&x, p, server_key);
     }
-    let c_blocks = (c.bits() as usize + 1) / 2;
-    let x = server_key.extend_radix_with_trivial_zero_blocks_msb(x, (NB * 2) - x.blocks().len());
+    mod_mersenne_with::<NB, _, _>(x, ctx, server_key)
+}
 
-    // first pass NB*2 blocks
-    let x_mod_p = (|x: &RadixCiphertext| {
-        let mut a = server_key.scalar_right_shift_parallelized(x, n as u64);
-        let mut b = server_key
-            .sub_parallelized(x, &server_key.scalar_left_shift_parallelized(&a, n as u64));
+/// homomorphic x mod p like `mod_mersenne_ctx` on any `FheInt` backend, e.g. `MockInt`.
+/// only for p with 2 * bits(c) < n, the Barrett fallback for a wide c is `RadixCiphertext` only.
+pub fn mod_mersenne_with<const NB: usize, P: Numeral, T: FheInt>(
+    x: &T,
+    ctx: &MersenneCtx<P>,
+    key: &T::Key,
+) -> T {
+    assert_eq!(
+        x.bits_per_block(),
+        ctx.bits_per_block,
+        "ctx was built for another block size"
+    );
+    assert!(
+        2 * ctx.c.bits() < ctx.n as u64,
+        "c must have less than n / 2 bits"
+    );
+    match x.num_blocks() > NB * 2 {
+        true => {
+            let x = fold_wide::<NB, _>(x, ctx.n, &ctx.c, key);
+            mersenne_passes::<NB, _, _>(&x, ctx, key)
+        }
+        false => mersenne_passes::<NB, _, _>(x, ctx, key),
+    }
+}
 
-        let len = x.blocks().len();
-        // a will be multiplied by c, so it must be at least NB + c_blocks long
-        server_key.trim_radix_blocks_msb_assign(&mut a, len - (NB + c_blocks));
+/// the 2 folding passes and the final subtraction of `mod_mersenne`, expect x < 2^(2n)
+fn mersenne_passes<const NB: usize, P: Numeral, T: FheInt>(
+    x: &T,
+    ctx: &MersenneCtx<P>,
+    key: &T::Key,
+) -> T {
+    let MersenneCtx {
+        p,
+        n,
+        ref c,
+        c_blocks,
+        ..
+    } = *ctx;
+    // with 2 * bits(c) < n, checked by `mod_mersenne_with`, a * c of the first pass fits in
+    // NB + c_blocks blocks and the second pass result is less than 2p, which is all the final
+    // subtraction can reduce
+    assert!(x.num_blocks() <= NB * 2, "x must fit in NB * 2 blocks");
+    let x = x.extend_msb((NB * 2) - x.num_blocks(), key);
+
+    // x = a * 2^n + b -> c * a + b with `a_blocks` blocks kept for c * a
+    let pass = |x: &T, a_blocks: usize| {
+        let a = x.scalar_right_shift(n as u64, key);
+        let a_shifted = a.scalar_left_shift(n as u64, key);
+        let b = ProtocolStats::time_op(ProtocolOps::Add, || x.sub(&a_shifted, key));
+
+        let len = x.num_blocks();
+        let a = a.trim_msb(len - a_blocks, key);
         // b must be at least NB long
-        server_key.trim_radix_blocks_msb_assign(&mut b, len - NB);
-        let ca = server_key.scalar_mul_parallelized(&a, bigint_to_u128(&c));
-        server_key.add_parallelized(&ca, &b)
-    })(&x);
+        let b = b.trim_msb(len - NB, key);
+        let ca = ProtocolStats::time_op(ProtocolOps::ScalarMul, || a.scalar_mul(c, key));
+        ProtocolStats::time_op(ProtocolOps::Add, || ca.add(&b, key))
+    };
+    // first pass NB*2 blocks, a will be multiplied by c so it must be at least NB + c_blocks long
+    let x_mod_p = pass(&x, NB + c_blocks);
+    // second pass % NB + c_blocks blocks, a must be at least NB + 1 long
+    let x_mod_p2 = pass(&x_mod_p, NB + 1);
+
+    let len = x_mod_p2.num_blocks();
+    x_mod_p2.sub_scalar_if_ge(p, key).trim_msb(len - NB, key)
+}
+
+/// homomorphic x mod p of x read as a two's complement value of NB * 2 blocks, e.g. the
+/// wrapped difference of 2 values, returns the positive residue. for k = NB * bits per block
+/// expect |x| < 2^(2k - 1), every value but -2^(2k - 1), and p^2 >= 2^(2k - 1).
+/// a negative x is shifted by p^2 first, the addition wraps around NB * 2 blocks.
+pub fn mod_mersenne_signed<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    assert!(x.blocks().len() <= NB * 2, "x must fit in NB * 2 blocks");
+    let x = server_key.extend_radix_with_trivial_zero_blocks_msb(x, (NB * 2) - x.blocks().len());
+    let bits = NB * 2 * bits_per_block(&x);
+    assert!(
+        to_bigint(p).pow(2).bits() as usize >= bits,
+        "p^2 must be at least 2^(2k - 1)"
+    );
+    if DEBUG_CHECKS {
+        read_client_key(|client_key| {
+            if client_key.parameters().message_modulus() == x.blocks()[0].message_modulus {
+                assert!(
+                    decrypt_bigint_wide(&x, client_key) != BigInt::from(1) << (bits - 1),
+                    "|x| must be below 2^(2k - 1)"
+                );
+            }
+        });
+    }
+    let is_neg = server_key.scalar_right_shift_parallelized(&x, (bits - 1) as u64);
+    let p2 = ProtocolStats::time_op(ProtocolOps::ScalarMul, || {
+        server_key.scalar_mul_parallelized(&server_key.scalar_mul_parallelized(&is_neg, p), p)
+    });
+    let x = ProtocolStats::time_op(ProtocolOps::Add, || server_key.add_parallelized(&x, &p2));
+    mod_mersenne::<NB, _>(&x, p, server_key)
+}
 
-    // second pass % NB + c_blocks blocks
-    let x_mod_p2 = (|x: &RadixCiphertext| {
+/// homomorphic x div p and x mod p, expect x < p^2 so both fit NB blocks.
+/// each pass of `mod_mersenne` writes x = a * 2^n + b = a * p + (c * a + b), so the quotient is
+/// the sum of the `a` of both passes plus 1 if the final subtraction of p happens.
+/// p with a wide c like `mod_mersenne` falls back to a scalar division.
+pub fn divmod_mersenne<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, RadixCiphertext) {
+    let ctx = MersenneCtx::cached(p, bits_per_block(x));
+    let MersenneCtx {
+        n, ref c, c_blocks, ..
+    } = *ctx;
+    assert!(x.blocks().len() <= NB * 2, "x must fit in NB * 2 blocks");
+    let x = server_key.extend_radix_with_trivial_zero_blocks_msb(x, (NB * 2) - x.blocks().len());
+    if c.bits() as u32 >= n / 2 || c.bits() > 128 {
+        let (mut q, mut r) = server_key.scalar_div_rem_parallelized(&x, p);
+        server_key.trim_radix_blocks_msb_assign(&mut q, NB);
+        server_key.trim_radix_blocks_msb_assign(&mut r, NB);
+        return (q, r);
+    }
+
+    // x = a * p + c * a + b with `a_blocks` blocks kept for c * a, a itself is <= x / p < p
+    let pass = |x: &RadixCiphertext, a_blocks: usize| {
         let mut a = server_key.scalar_right_shift_parallelized(x, n as u64);
-        let mut b = server_key
-            .sub_parallelized(x, &server_key.scalar_left_shift_parallelized(&a, n as u64));
+        let a_shifted = server_key.scalar_left_shift_parallelized(&a, n as u64);
+        let mut b = ProtocolStats::time_op(ProtocolOps::Add, || {
+            server_key.sub_parallelized(x, &a_shifted)
+        });
 
         let len = x.blocks().len();
-        // a will be multiplied by c, so it must be at least NB + 1 long
-        server_key.trim_radix_blocks_msb_assign(&mut a, len - (NB + 1));
-        // b must be at least NB long
+        server_key.trim_radix_blocks_msb_assign(&mut a, len - a_blocks);
         server_key.trim_radix_blocks_msb_assign(&mut b, len - NB);
-        let ca = server_key.scalar_mul_parallelized(&a, bigint_to_u128(&c));
-        server_key.add_parallelized(&ca, &b)
-    })(&x_mod_p);
+        let ca = ProtocolStats::time_op(ProtocolOps::ScalarMul, || {
+            scalar_mul_sparse(&a, c, server_key)
+        });
+        let folded =
+            ProtocolStats::time_op(ProtocolOps::Add, || server_key.add_parallelized(&ca, &b));
+        server_key.trim_radix_blocks_msb_assign(&mut a, a_blocks - NB);
+        (a, folded)
+    };
+    let (a0, x) = pass(&x, NB + c_blocks);
+    let (a1, mut x) = pass(&x, NB + 1);
+
+    // x < 2p, subtract p once
+    let len = x.blocks().len();
+    let mut is_ge: RadixCiphertext = server_key
+        .scalar_ge_parallelized(&x, p)
+        .into_radix(NB - 1, server_key);
+    let is_ge_len = is_ge.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut is_ge, is_ge_len - 1);
+    let to_sub = selector_zero_constant::<NB, _>(p, &is_ge, server_key);
+    server_key.sub_assign_parallelized(&mut x, &to_sub);
+    server_key.trim_radix_blocks_msb_assign(&mut x, len - NB);
+    let is_ge = server_key.extend_radix_with_trivial_zero_blocks_msb(&is_ge, NB - 1);
+    let q = ProtocolStats::time_op(ProtocolOps::Add, || {
+        server_key.add_parallelized(&server_key.add_parallelized(&a0, &a1), &is_ge)
+    });
+
+    (q, x)
+}
+
+/// homomorphic x mod p for p = 2^k - c using all the k = NB * bits per block bits of NB blocks.
+/// x = hi * 2^k + lo is folded into c * hi + lo where the split is a block split without any shift.
+/// unlike `mod_mersenne` c can be wide, the number of folds follows from the bound of x
+/// e.g. 4 folds for the secp256k1 group order with a 129 bits c.
+/// expect x < p^2
+pub fn mod_mersenne_fold<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    ProtocolStats::time_op(ProtocolOps::ModReduce, || {
+        fold_bounded::<NB, _>(x, p, (to_bigint(p) - 1).pow(2), server_key).0
+    })
+}
+
+/// `mod_mersenne_fold` of x <= `bound`, x is resized to the blocks of `bound` before folding.
+/// also returns the blocks of the widest value it folded
+fn fold_bounded<const NB: usize, P: Numeral>(
+    x: &RadixCiphertext,
+    p: P,
+    mut bound: BigInt,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, usize) {
+    let bits_per_block = bits_per_block(x);
+    let k = NB * bits_per_block;
+    let p_bigint = to_bigint(p);
+    assert_eq!(
+        p_bigint.bits() as usize,
+        k,
+        "p must use all the bits of NB blocks"
+    );
+    let mask = (BigInt::from(1) << k) - 1;
+    let c_bigint = &mask + 1 - &p_bigint;
+    let c = from_bigint::<P>(&c_bigint).expect("c < p fits P");
+    let blocks =
+        |bound: &BigInt| ((bound.bits() as usize + bits_per_block - 1) / bits_per_block).max(NB);
+    let resize = |x: &RadixCiphertext, len: usize| {
+        let x_len = x.blocks().len();
+        match x_len > len {
+            true => server_key.trim_radix_blocks_msb(x, x_len - len),
+            false => server_key.extend_radix_with_trivial_zero_blocks_msb(x, len - x_len),
+        }
+    };
+
+    let mut x = resize(x, blocks(&bound));
+    let mut peak = x.blocks().len();
+    while bound > mask {
+        // the largest c * hi + lo for any value up to bound
+        let hi_max = &bound >> k;
+        bound = (&c_bigint * &hi_max + (&bound & &mask)).max(&c_bigint * (&hi_max - 1) + &mask);
+        let len = blocks(&bound);
+        let (mut hi, lo) = rayon::join(
+            || resize(&server_key.trim_radix_blocks_lsb(&x, NB), len),
+            || {
+                resize(
+                    &server_key.trim_radix_blocks_msb(&x, x.blocks().len() - NB),
+                    len,
+                )
+            },
+        );
+        server_key.scalar_mul_assign_parallelized(&mut hi, c);
+        x = server_key.add_parallelized(&hi, &lo);
+        peak = peak.max(len);
+    }
+
+    // x < 2^k < 2p
+    let res = modulo_fast_inner::<NB, _>(&resize(&x, NB), p, server_key);
+    (res, peak)
+}
+
+/// homomorphic a * b mod p with p like `mod_mersenne_fold`, without the NB * 2 blocks product.
+/// b is consumed `chunk_blocks` blocks at a time from the most significant end,
+/// acc = acc * 2^w + a * b_i is folded back below p after each chunk so no intermediate is wider
+/// than `mul_then_reduce_peak_blocks`. trades NB / chunk_blocks reductions for the memory.
+pub fn mul_then_reduce<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    p: P,
+    chunk_blocks: usize,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    mul_then_reduce_with_peak::<NB, _>(a, b, p, chunk_blocks, server_key).0
+}
+
+/// `mul_then_reduce` that also returns the blocks of the widest ciphertext it built,
+/// the operands of the chunk products and of the folds included
+pub fn mul_then_reduce_with_peak<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    p: P,
+    chunk_blocks: usize,
+    server_key: &ServerKey,
+) -> (RadixCiphertext, usize) {
+    assert!(
+        chunk_blocks > 0 && chunk_blocks < NB,
+        "chunk must be 1 to NB - 1 blocks"
+    );
+    let bits_per_block = bits_per_block(a);
+    let p_bigint = to_bigint(p);
+    let mut acc = server_key.create_trivial_radix(0, NB);
+    let mut peak = NB;
+    let mut end = NB;
+    while end > 0 {
+        let start = end.saturating_sub(chunk_blocks);
+        let width = end - start;
+        // b_i = b[start..end]
+        let b_i =
+            server_key.trim_radix_blocks_msb(&server_key.trim_radix_blocks_lsb(b, start), NB - end);
+        let (acc_shifted, ab_i) = rayon::join(
+            || server_key.extend_radix_with_trivial_zero_blocks_lsb(&acc, width),
+            || {
+                let mut ab_i = server_key.extend_radix_with_trivial_zero_blocks_msb(a, width);
+                let b_i = server_key.extend_radix_with_trivial_zero_blocks_msb(&b_i, NB);
+                ProtocolStats::time_op(ProtocolOps::Mul, || {
+                    server_key.mul_assign_parallelized(&mut ab_i, &b_i)
+                });
+                ab_i
+            },
+        );
+        let mut t = server_key.extend_radix_with_trivial_zero_blocks_msb(&acc_shifted, 1);
+        let ab_i = server_key.extend_radix_with_trivial_zero_blocks_msb(&ab_i, 1);
+        ProtocolStats::time_op(ProtocolOps::Add, || {
+            server_key.add_assign_parallelized(&mut t, &ab_i)
+        });
+        // t <= (p - 1) * 2^w + (p - 1) * (2^w - 1)
+        let bound = (&p_bigint - 1) * ((BigInt::from(1) << (width * bits_per_block + 1)) - 1);
+        let (folded, fold_peak) = ProtocolStats::time_op(ProtocolOps::ModReduce, || {
+            fold_bounded::<NB, _>(&t, p, bound, server_key)
+        });
+        peak = peak.max(t.blocks().len()).max(fold_peak);
+        acc = folded;
+        end = start;
+    }
+
+    (acc, peak)
+}
 
-    modulo_fast::<NB, _>(&x_mod_p2, p, server_key)
+/// widest intermediate of `mul_then_reduce` in blocks, against NB * 2 for `mul_mod_mersenne`:
+/// the sum acc * 2^w + a * b_i of a full chunk with 1 carry block. an estimate for 2 bits
+/// blocks, `mul_then_reduce_with_peak` measures the real one
+pub fn mul_then_reduce_peak_blocks(num_block: usize, chunk_blocks: usize) -> usize {
+    num_block + chunk_blocks + 1
 }
 
 /// homomorphic a * b mod p
+/// a is extended to NB * 2 blocks so the product of 2 values < p fits without overflow, the
+/// default `mul_assign_parallelized` propagates carries of dirty inputs before multiplying.
 pub fn mul_mod_mersenne<const NB: usize, P: Numeral>(
     a: &RadixCiphertext,
     b: &RadixCiphertext,
     p: P,
     server_key: &ServerKey,
+) -> RadixCiphertext {
+    let ctx = MersenneCtx::cached(p, bits_per_block(a));
+    mul_mod_mersenne_ctx::<NB, _>(a, b, &ctx, server_key)
+}
+
+/// homomorphic a * b mod p like `mul_mod_mersenne` with precomputed `ctx`
+pub fn mul_mod_mersenne_ctx<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    ctx: &MersenneCtx<P>,
+    server_key: &ServerKey,
 ) -> RadixCiphertext {
     let mut a_expanded = server_key.extend_radix_with_trivial_zero_blocks_msb(a, NB);
-    server_key.mul_assign_parallelized(&mut a_expanded, b);
-    mod_mersenne::<NB, _>(&a_expanded, p, server_key)
+    ProtocolStats::time_op(ProtocolOps::Mul, || {
+        server_key.mul_assign_parallelized(&mut a_expanded, b)
+    });
+    mod_mersenne_ctx::<NB, _>(&a_expanded, ctx, server_key)
+}
+
+/// seed of the operands of `bench_reduction` so runs compare the same multiplications
+pub const BENCH_REDUCTION_SEED: u64 = 42;
+
+/// time one `mul_mod_mersenne` of NB blocks operands for each of `primes`, to pick a prime.
+/// returns `(p, time)` in the order of `primes`. the operands are drawn % p from a rng seeded
+/// with `BENCH_REDUCTION_SEED` and the mul and reduction are also counted in `ProtocolStats`.
+pub fn bench_reduction<const NB: usize>(
+    primes: &[U256],
+    server_key: &ServerKey,
+    client_key: &ClientKey,
+) -> Vec<(U256, Duration)> {
+    let mut rng = StdRng::seed_from_u64(BENCH_REDUCTION_SEED);
+    primes
+        .iter()
+        .map(|p| {
+            let mut random = || {
+                let value = U256::from((rng.gen::<u128>(), rng.gen::<u128>()));
+                client_key.encrypt_radix(modulo_native(value, *p), NB)
+            };
+            let (a, b) = (random(), random());
+            assert!(
+                required_blocks(&to_bigint(*p), bits_per_block(&a)) <= NB,
+                "{} does not fit NB = {} blocks",
+                format(*p),
+                NB
+            );
+            let now = Instant::now();
+            mul_mod_mersenne::<NB, _>(&a, &b, *p, server_key);
+            (*p, now.elapsed())
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Instant;
+    use std::{sync::Arc, time::Instant};
 
     use num_bigint::BigInt;
+    use rand::{rngs::OsRng, Rng};
     use tfhe::{
-        integer::{keycache::IntegerKeyCache, IntegerKeyKind},
-        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+        integer::{keycache::IntegerKeyCache, IntegerCiphertext, IntegerKeyKind, U256, U512},
+        shortint::{parameters::PARAM_MESSAGE_4_CARRY_4_KS_PBS, prelude::PARAM_MESSAGE_2_CARRY_2},
     };
 
-    use crate::ops::{
-        mersenne::{mersenne_mod_native, mul_mod_mersenne},
-        native::mul_mod_native,
+    use crate::{
+        helper::{barrett_mu, bigint_to_u128, format, from_bigint, montgomery_params, to_bigint},
+        ops::{
+            backend::FheInt,
+            barrett::mod_barrett,
+            mersenne::{
+                bench_reduction, divmod_mersenne, mersenne_mod_native, mersenne_mod_signed_native,
+                mod_mersenne, mod_mersenne_fold, mod_mersenne_signed, mod_mersenne_with,
+                mul_mod_mersenne, mul_mod_mersenne_ctx, mul_then_reduce_peak_blocks,
+                mul_then_reduce_with_peak, scalar_mul_sparse, scalar_mul_trivial, MersenneCtx,
+            },
+            mock::MockInt,
+            modulo_fast,
+            montgomery::montgomery_reduce,
+            native::mul_mod_native,
+            secp256k1::prelude::{FQ_MODULO, FR_MODULO},
+        },
+        stats::ProtocolStats,
     };
 
     use crate::ecdsa;
 
-    use super::{mersenne_coeff, mersenne_coeff_p};
+    use super::{check_mod, mersenne_coeff, mersenne_coeff_p};
 
     #[test]
     fn correct_mersenne_native_mod() {
@@ -176,6 +747,215 @@ mod tests {
         assert_eq!(mersenne_mod_native(x * y, p), mul_mod_native(x, y, p));
     }
 
+    #[test]
+    fn correct_mersenne_mod_signed_native() {
+        let p: u16 = 251;
+        for x in [-5i16, -251, -252, -31125, 0, 5, 251 * 100] {
+            assert_eq!(
+                mersenne_mod_signed_native(x as u16, p),
+                x.rem_euclid(p as i16) as u16,
+                "x {}",
+                x
+            );
+        }
+        // the whole range of i32 but i32::MIN, p^2 > 2^31
+        let p: u32 = 65521;
+        for x in [i32::MIN + 1, -(65520 * 32768), -1, i32::MAX] {
+            assert_eq!(
+                mersenne_mod_signed_native(x as u32, p),
+                x.rem_euclid(p as i32) as u32,
+                "x {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "|x| must be below 2^(bits - 1)")]
+    fn reject_mersenne_mod_signed_native_min() {
+        mersenne_mod_signed_native(i16::MIN as u16, 251);
+    }
+
+    #[test]
+    #[should_panic(expected = "p^2 must be at least 2^(bits - 1)")]
+    fn reject_mersenne_mod_signed_native_small_p() {
+        mersenne_mod_signed_native(5u32, 251);
+    }
+
+    #[test]
+    fn correct_mod_mersenne_signed() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        // 251 takes the Mersenne passes, 199 falls back to Barrett
+        for p in [251i32, 199] {
+            for x in [-1, -p, -(p * p / 2), -(1 << 15) + 1, 7, (1 << 15) - 1] {
+                // x as a 16 bits two's complement value in NB * 2 blocks, |x| < 2^15
+                let enc_x = client_key.encrypt_radix(x as u16, NUM_BLOCK * 2);
+                let res = mod_mersenne_signed::<NUM_BLOCK, _>(&enc_x, p as u8, &server_key);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&res),
+                    x.rem_euclid(p) as u8,
+                    "x {} p {}",
+                    x,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn correct_scalar_mul_sparse() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 8;
+
+        // 977 = 2^10 - 2^6 + 2^4 + 1, 0x1555 has NAF weight 7 and takes the scalar mul
+        for c in [1u16, 5, 57, 255, 977, 0x1555] {
+            for a in [0u16, 1, 250, u16::MAX] {
+                let res = scalar_mul_sparse(
+                    &client_key.encrypt_radix(a, NUM_BLOCK),
+                    &BigInt::from(c),
+                    &server_key,
+                );
+                assert_eq!(res.blocks().len(), NUM_BLOCK);
+                assert_eq!(
+                    client_key.decrypt_radix::<u16>(&res),
+                    a.wrapping_mul(c),
+                    "{} * {}",
+                    a,
+                    c
+                );
+            }
+        }
+    }
+
+    /// a * c of mod_mersenne's first pass on secp256k1's field prime, c = 2^32 + 977 with NAF
+    /// weight 5. run with `cargo test --release bench_scalar_mul_sparse -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_scalar_mul_sparse() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        let ctx = MersenneCtx::new(*FQ_MODULO, 2);
+        let num_block = 128 + ctx.c_blocks;
+        let a = U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>()));
+        let enc_a = client_key.encrypt_radix(a, num_block);
+
+        let now = Instant::now();
+        let sparse = scalar_mul_sparse(&enc_a, &ctx.c, &server_key);
+        let sparse_time = now.elapsed().as_secs_f64();
+        let now = Instant::now();
+        let dense = server_key.scalar_mul_parallelized(&enc_a, bigint_to_u128(&ctx.c));
+        let dense_time = now.elapsed().as_secs_f64();
+        assert_eq!(
+            client_key.decrypt_radix::<U512>(&sparse),
+            client_key.decrypt_radix::<U512>(&dense)
+        );
+        println!(
+            "shift and add {:.2}s, scalar mul {:.2}s",
+            sparse_time, dense_time
+        );
+    }
+
+    #[test]
+    fn correct_check_mod() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let x = client_key.encrypt_radix(250u16 * 249, NUM_BLOCK * 2);
+        let res = mod_mersenne::<NUM_BLOCK, _>(&x, 251u8, &server_key);
+        check_mod(&x, &res, 251u8, &client_key);
+    }
+
+    #[test]
+    #[should_panic(expected = "x mod p of x = 62250 p = 251")]
+    fn reject_check_mod_wrong_result() {
+        let (client_key, _) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        // 62250 mod 251 = 2
+        let x = client_key.encrypt_radix(250u16 * 249, NUM_BLOCK * 2);
+        check_mod(
+            &x,
+            &client_key.encrypt_radix(3u8, NUM_BLOCK),
+            251u8,
+            &client_key,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit u128")]
+    fn reject_scalar_mul_sparse_wide_c() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        // the 129 bits c of the secp256k1 group order would lose its top bit as a u128
+        let ctx = MersenneCtx::new(*FR_MODULO, 2);
+        assert_eq!(ctx.c.bits(), 129);
+        scalar_mul_sparse(&client_key.encrypt_radix(1u8, 4), &ctx.c, &server_key);
+    }
+
+    #[test]
+    fn correct_scalar_mul_trivial() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        // 2^130 + 3 is 3 in 4 blocks of 2 bits
+        let c = (BigInt::from(1) << 130) + 3;
+        let enc_a = client_key.encrypt_radix(57u8, NUM_BLOCK);
+        let res = scalar_mul_trivial(&enc_a, &c, &server_key);
+        assert_eq!(res.blocks().len(), NUM_BLOCK);
+        assert_eq!(client_key.decrypt_radix::<u8>(&res), 57 * 3);
+        let res = FheInt::scalar_mul(&enc_a, &c, &server_key);
+        assert_eq!(client_key.decrypt_radix::<u8>(&res), 57 * 3);
+    }
+
+    /// p = 2^260 - 2^128 - 199 has a 129 bits c, the passes multiply by it as a trivial
+    /// ciphertext. run with `cargo test --release correct_mod_mersenne_wide_c -- --ignored`
+    #[test]
+    #[ignore]
+    fn correct_mod_mersenne_wide_c() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 130;
+        let (n, p, _, c) = mersenne_coeff(&[260, 128, 199]);
+        assert!(c.bits() > 128 && 2 * c.bits() < n as u64);
+        let p = from_bigint::<U512>(&p).unwrap();
+
+        let (x, y) = (p - U512::from(1u8), p - U512::from(2u8));
+        let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+        let enc_y = client_key.encrypt_radix(y, NUM_BLOCK);
+        let xy_mod_p = mul_mod_mersenne::<NUM_BLOCK, _>(&enc_x, &enc_y, p, &server_key);
+        assert_eq!(
+            client_key.decrypt_radix::<U512>(&xy_mod_p),
+            mul_mod_native(x, y, p)
+        );
+    }
+
+    #[test]
+    fn correct_mod_mersenne_wide() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        // 251 folds the 3 * NB blocks down first, 199 falls back to a division
+        for p in [251u32, 199] {
+            for x in [0, p * p - 1, p * p * p + 7, (1 << 24) - 1] {
+                let enc_x = client_key.encrypt_radix(x, NUM_BLOCK * 3);
+                let res = mod_mersenne::<NUM_BLOCK, _>(&enc_x, p as u8, &server_key);
+                assert_eq!(res.blocks().len(), NUM_BLOCK);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&res),
+                    (x % p) as u8,
+                    "x {} p {}",
+                    x,
+                    p
+                );
+            }
+        }
+    }
+
     #[test]
     fn correct_mersenne_mul_mod() {
         let (client_key, server_key) =
@@ -201,10 +981,374 @@ mod tests {
         );
     }
 
+    #[test]
+    fn correct_mersenne_mul_mod_ctx() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        // 251 takes the Mersenne passes, 199 falls back to Barrett
+        for p in [251u8, 199] {
+            let ctx = MersenneCtx::new(p, 2);
+            for _ in 0..4 {
+                let x = OsRng.gen_range(0..p);
+                let y = OsRng.gen_range(0..p);
+                let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+                let enc_y = client_key.encrypt_radix(y, NUM_BLOCK);
+                let cached =
+                    mul_mod_mersenne_ctx::<NUM_BLOCK, _>(&enc_x, &enc_y, &ctx, &server_key);
+                let uncached = mul_mod_mersenne::<NUM_BLOCK, _>(&enc_x, &enc_y, p, &server_key);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&cached),
+                    client_key.decrypt_radix::<u8>(&uncached)
+                );
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&cached),
+                    mul_mod_native(x, y, p)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn correct_divmod_mersenne() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        // 251 takes the Mersenne passes, 199 falls back to the scalar division
+        for p in [251u8, 199] {
+            let max = p as u16 * p as u16 - 1;
+            for x in [0, p as u16 - 1, p as u16, max, OsRng.gen_range(0..=max)] {
+                let enc_x = client_key.encrypt_radix(x, NUM_BLOCK * 2);
+                let (q, r) = divmod_mersenne::<NUM_BLOCK, _>(&enc_x, p, &server_key);
+                assert_eq!(q.blocks().len(), NUM_BLOCK);
+                assert_eq!(r.blocks().len(), NUM_BLOCK);
+                let q = client_key.decrypt_radix::<u16>(&q);
+                let r = client_key.decrypt_radix::<u16>(&r);
+                assert!(r < p as u16, "x {} p {}", x, p);
+                assert_eq!(q * p as u16 + r, x, "x {} p {}", x, p);
+            }
+        }
+    }
+
+    #[test]
+    fn correct_bench_reduction() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let primes = [251u8, 241, 199].map(U256::from);
+
+        let timings = bench_reduction::<NUM_BLOCK>(&primes, &server_key, &client_key);
+        assert_eq!(
+            timings.iter().map(|(p, _)| *p).collect::<Vec<_>>(),
+            primes.to_vec()
+        );
+        for (p, time) in timings {
+            println!("p = {}: {:.2}s", format(p), time.as_secs_f64());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit NB = 4 blocks")]
+    fn reject_bench_reduction_wide_prime() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        bench_reduction::<4>(&[U256::from(257u16)], &server_key, &client_key);
+    }
+
+    #[test]
+    fn correct_mersenne_ctx_cached() {
+        let ctx = MersenneCtx::cached(*FQ_MODULO, 2);
+        assert_eq!(*ctx, MersenneCtx::new(*FQ_MODULO, 2));
+        assert_eq!(ctx.c_blocks, 17);
+        // built once per modulus, block size and type of p
+        assert!(Arc::ptr_eq(&ctx, &MersenneCtx::cached(*FQ_MODULO, 2)));
+        assert!(!Arc::ptr_eq(&ctx, &MersenneCtx::cached(*FR_MODULO, 2)));
+        assert_eq!(MersenneCtx::cached(*FQ_MODULO, 4).c_blocks, 9);
+        assert_eq!(MersenneCtx::cached(251u8, 2).p, 251);
+        assert_eq!(MersenneCtx::cached(251u16, 2).p, 251);
+    }
+
+    /// the reduction alone of `mod_mersenne` on secp256k1's field prime, the ctx comes from the
+    /// cache like in the signing path. `MockInt` runs the same passes without the FHE cost.
+    #[test]
+    fn bench_mod_mersenne() {
+        const NUM_BLOCK: usize = 128;
+        const ITERATIONS: u32 = 1000;
+        let p = *FQ_MODULO;
+        let p_bigint = to_bigint(p);
+        let x = &p_bigint * &p_bigint - 1;
+        let enc_x = MockInt::new(from_bigint::<U512>(&x).unwrap(), NUM_BLOCK * 2, 2);
+
+        let now = Instant::now();
+        for _ in 0..ITERATIONS {
+            let ctx = MersenneCtx::cached(p, 2);
+            std::hint::black_box(mod_mersenne_with::<NUM_BLOCK, _, _>(
+                std::hint::black_box(&enc_x),
+                &ctx,
+                &(),
+            ));
+        }
+        let elapsed = now.elapsed();
+        println!(
+            "mod mersenne for secp256k1 p done in {:.2}us per reduction",
+            elapsed.as_secs_f64() * 1e6 / ITERATIONS as f64
+        );
+        let ctx = MersenneCtx::cached(p, 2);
+        let res = mod_mersenne_with::<NUM_BLOCK, _, _>(&enc_x, &ctx, &());
+        assert_eq!(to_bigint(res.value::<U256>()), x % p_bigint);
+    }
+
+    #[test]
+    fn correct_reduction_stats_by_op() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let enc_x = client_key.encrypt_radix(250u8, NUM_BLOCK);
+        let enc_y = client_key.encrypt_radix(249u8, NUM_BLOCK);
+        // below p^2 and n * R for p = n = 199 and R = 2^8
+        let enc_xy = client_key.encrypt_radix(198u16 * 198, NUM_BLOCK * 2);
+        let count = |stats: &ProtocolStats, op: &str| stats.by_op().get(op).map_or(0, |s| s.count);
+
+        // 1 mul, then 2 passes of 1 scalar mul, 1 sub and 1 add in a single reduction
+        let (_, stats) = ProtocolStats::collect(2, || {
+            mul_mod_mersenne::<NUM_BLOCK, _>(&enc_x, &enc_y, 251u8, &server_key)
+        });
+        let ops = ["mul", "scalar_mul", "add", "mod_reduce"];
+        assert_eq!(ops.map(|op| count(&stats, op)), [1, 2, 4, 1]);
+
+        // the other reductions count 1 each, their final `modulo_fast` included, and
+        // `montgomery_reduce` records its 2 scalar muls and 1 add
+        let p = 199u8;
+        let (_, n_prime) = montgomery_params(&to_bigint(p), NUM_BLOCK, 2);
+        let n_prime = from_bigint::<u8>(&n_prime).unwrap();
+        let (_, stats) = ProtocolStats::collect(2, || {
+            mod_barrett::<NUM_BLOCK, _>(&enc_xy, p, &barrett_mu(&to_bigint(p)), &server_key);
+            montgomery_reduce::<NUM_BLOCK, _>(&enc_xy, p, n_prime, &server_key);
+            modulo_fast::<NUM_BLOCK, _>(
+                &client_key.encrypt_radix(250u16, NUM_BLOCK + 1),
+                p,
+                &server_key,
+            );
+        });
+        assert_eq!(ops.map(|op| count(&stats, op)), [0, 2, 1, 3]);
+    }
+
+    #[test]
+    fn correct_mersenne_mul_mod_4_bits_block() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_4_CARRY_4_KS_PBS, IntegerKeyKind::Radix);
+        // 8 bits in 2 blocks of 4 bits
+        const NUM_BLOCK: usize = 2;
+
+        // 251 = 2^8 - 5 takes the mersenne path, 157 = 2^8 - 99 the barrett path
+        for p in [251u8, 157] {
+            for (x, y) in [(p - 1, p - 2), (123 % p, 234 % p), (0, p - 1)] {
+                let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+                let enc_y = client_key.encrypt_radix(y, NUM_BLOCK);
+                let xy_mod_p = mul_mod_mersenne::<NUM_BLOCK, _>(&enc_x, &enc_y, p, &server_key);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&xy_mod_p),
+                    mul_mod_native(x, y, p)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn correct_mersenne_mul_mod_multi_block_c() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 32;
+        // p = 2^64 - c with c = 1048605 spanning 11 blocks
+        let p: u64 = 18446744073708503011;
+        assert_eq!(mersenne_coeff_p(p), (64, BigInt::from(1048605)));
+
+        for (x, y) in [
+            (p - 1, p - 1),
+            (p - 1, p - 2),
+            (1 << 63, p - 1048605),
+            (0, p - 1),
+        ] {
+            let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+            let enc_y = client_key.encrypt_radix(y, NUM_BLOCK);
+            let xy_mod_p = mul_mod_mersenne::<NUM_BLOCK, _>(&enc_x, &enc_y, p, &server_key);
+            assert_eq!(
+                client_key.decrypt_radix::<u64>(&xy_mod_p),
+                mul_mod_native(x, y, p)
+            );
+        }
+    }
+
+    /// regression for c over 128 bits, which the passes used to multiply as a truncated u128,
+    /// e.g. 2^260 + 5 gave 199 + 5 instead of c + 5 for p = 2^260 - 2^128 - 199. runs on
+    /// `MockInt` since 130 blocks of `RadixCiphertext` take minutes, see
+    /// `correct_mod_mersenne_wide_c`.
+    #[test]
+    fn correct_mod_mersenne_c_over_u128() {
+        const NUM_BLOCK: usize = 130;
+        let (_, p_bigint, _, c) = mersenne_coeff(&[260, 128, 199]);
+        let p = from_bigint::<U512>(&p_bigint).unwrap();
+        let ctx = MersenneCtx::new(p, 2);
+        let x = (BigInt::from(1) << 260) + 5;
+        let truncated = (&c % (BigInt::from(1) << 128)) + 5;
+        assert_ne!(truncated, &x % &p_bigint);
+
+        let value = MockInt::new(from_bigint::<U512>(&x).unwrap(), NUM_BLOCK * 2, 2);
+        let res = mod_mersenne_with::<NUM_BLOCK, _, _>(&value, &ctx, &());
+        assert_eq!(to_bigint(res.value::<U512>()), c + 5);
+    }
+
+    #[test]
+    fn correct_mersenne_mul_mod_max_operands() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 128;
+        let p = *FQ_MODULO;
+        let x = p - U256::ONE;
+
+        // (p - 1)^2 fills all NB * 2 blocks of the product
+        let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+        let xx_mod_p = mul_mod_mersenne::<NUM_BLOCK, _>(&enc_x, &enc_x, p, &server_key);
+        assert_eq!(client_key.decrypt_radix::<U256>(&xx_mod_p), U256::ONE);
+
+        // operands with pending carries from an unpropagated add
+        let enc_y = server_key.unchecked_add(
+            &client_key.encrypt_radix(p - U256::from(2u8), NUM_BLOCK),
+            &client_key.encrypt_radix(U256::ONE, NUM_BLOCK),
+        );
+        let xy_mod_p = mul_mod_mersenne::<NUM_BLOCK, _>(&enc_y, &enc_x, p, &server_key);
+        assert_eq!(
+            client_key.decrypt_radix::<U256>(&xy_mod_p),
+            mul_mod_native(x, x, p)
+        );
+    }
+
+    /// random x < p^2 against num_bigint, `MERSENNE_FUZZ_ITERATIONS` sets the number of samples
+    /// per prime, e.g. `MERSENNE_FUZZ_ITERATIONS=1000 cargo test --release fuzz_mod_mersenne`
+    #[test]
+    fn fuzz_mod_mersenne() {
+        let iterations = std::env::var("MERSENNE_FUZZ_ITERATIONS")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(4);
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+
+        // 251 = 2^8 - 5 in 4 blocks
+        const NUM_BLOCK_SMALL: usize = 4;
+        let p: u8 = 251;
+        for _ in 0..iterations {
+            let x = OsRng.gen_range(0..p as u16 * p as u16);
+            let enc_x = client_key.encrypt_radix(x, NUM_BLOCK_SMALL * 2);
+            let res = mod_mersenne::<NUM_BLOCK_SMALL, _>(&enc_x, p, &server_key);
+            assert_eq!(
+                BigInt::from(client_key.decrypt_radix::<u8>(&res)),
+                BigInt::from(x) % BigInt::from(p),
+                "{} mod {}",
+                x,
+                p
+            );
+        }
+
+        // 2^64 - 1048605 in 32 blocks
+        const NUM_BLOCK: usize = 32;
+        let p: u64 = 18446744073708503011;
+        for _ in 0..iterations {
+            let x = OsRng.gen_range(0..p as u128 * p as u128);
+            let enc_x = client_key.encrypt_radix(x, NUM_BLOCK * 2);
+            let res = mod_mersenne::<NUM_BLOCK, _>(&enc_x, p, &server_key);
+            assert_eq!(
+                BigInt::from(client_key.decrypt_radix::<u64>(&res)),
+                BigInt::from(x) % BigInt::from(p),
+                "{} mod {}",
+                x,
+                p
+            );
+        }
+    }
+
+    #[test]
+    fn correct_mod_mersenne_fold() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        // c = 5, 57 and 99 need 3, 5 and 7 folds
+        for p in [251u8, 199, 157] {
+            for (x, y) in [
+                (p - 1, p - 1),
+                (p - 1, p - 2),
+                (123 % p, 234 % p),
+                (0, p - 1),
+            ] {
+                let enc_xy = client_key.encrypt_radix(x as u16 * y as u16, NUM_BLOCK * 2);
+                let res = mod_mersenne_fold::<NUM_BLOCK, _>(&enc_xy, p, &server_key);
+                assert_eq!(
+                    client_key.decrypt_radix::<u8>(&res),
+                    mul_mod_native(x, y, p),
+                    "{} * {} mod {}",
+                    x,
+                    y,
+                    p
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn correct_mul_then_reduce() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+
+        for p in [251u8, 199] {
+            for (x, y) in [(p - 1, p - 1), (123 % p, 234 % p), (0, p - 1)] {
+                let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+                let enc_y = client_key.encrypt_radix(y, NUM_BLOCK);
+                let two_phase = mod_mersenne_fold::<NUM_BLOCK, _>(
+                    &server_key.mul_parallelized(
+                        &server_key.extend_radix_with_trivial_zero_blocks_msb(&enc_x, NUM_BLOCK),
+                        &server_key.extend_radix_with_trivial_zero_blocks_msb(&enc_y, NUM_BLOCK),
+                    ),
+                    p,
+                    &server_key,
+                );
+                for chunk_blocks in [1, 3] {
+                    let (fused, peak) = mul_then_reduce_with_peak::<NUM_BLOCK, _>(
+                        &enc_x,
+                        &enc_y,
+                        p,
+                        chunk_blocks,
+                        &server_key,
+                    );
+                    assert_eq!(peak, mul_then_reduce_peak_blocks(NUM_BLOCK, chunk_blocks));
+                    assert_eq!(
+                        client_key.decrypt_radix::<u8>(&fused),
+                        client_key.decrypt_radix::<u8>(&two_phase)
+                    );
+                    assert_eq!(
+                        client_key.decrypt_radix::<u8>(&fused),
+                        mul_mod_native(x, y, p),
+                        "{} * {} mod {} in chunks of {}",
+                        x,
+                        y,
+                        p,
+                        chunk_blocks
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn correct_mersenne_transfrom() {
         let p: u8 = 127;
         let coeff = mersenne_coeff_p(p);
         assert_eq!(coeff, (7, BigInt::from(1)));
+        assert_eq!(mersenne_coeff_p(2u8), (2, BigInt::from(2)));
+        assert_eq!(mersenne_coeff_p(3u8), (2, BigInt::from(1)));
+        assert_eq!(mersenne_coeff_p(u64::MAX >> 3), (61, BigInt::from(1)));
     }
 }
@@ -0,0 +1,204 @@
+//! plaintext `FheInt` backend, the same block layout and wrapping as `RadixCiphertext`
+//! on a `BigInt` so the algorithms run in microseconds instead of minutes.
+
+use num_bigint::BigInt;
+
+use crate::{
+    helper::{from_bigint_unchecked, to_bigint},
+    numeral::Numeral,
+};
+
+use super::backend::FheInt;
+
+/// a plaintext radix integer of `num_blocks` blocks of `bits_per_block` bits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockInt {
+    value: BigInt,
+    num_blocks: usize,
+    bits_per_block: usize,
+}
+
+impl MockInt {
+    /// `value` mod 2^(num_blocks * bits_per_block), like an encryption of `value`
+    pub fn new<P: Numeral>(value: P, num_blocks: usize, bits_per_block: usize) -> Self {
+        Self::from_bigint(to_bigint(value), num_blocks, bits_per_block)
+    }
+
+    fn from_bigint(value: BigInt, num_blocks: usize, bits_per_block: usize) -> Self {
+        let modulus = BigInt::from(1) << (num_blocks * bits_per_block);
+        MockInt {
+            value: ((value % &modulus) + &modulus) % modulus,
+            num_blocks,
+            bits_per_block,
+        }
+    }
+
+    /// the value as `P`, like a decryption
+    pub fn value<P: Numeral>(&self) -> P {
+        from_bigint_unchecked(&self.value)
+    }
+
+    fn with_value(&self, value: BigInt) -> Self {
+        Self::from_bigint(value, self.num_blocks, self.bits_per_block)
+    }
+}
+
+impl FheInt for MockInt {
+    type Key = ();
+
+    fn num_blocks(&self) -> usize {
+        self.num_blocks
+    }
+
+    fn bits_per_block(&self) -> usize {
+        self.bits_per_block
+    }
+
+    fn scalar_left_shift(&self, shift: u64, _key: &()) -> Self {
+        self.with_value(&self.value << shift)
+    }
+
+    fn scalar_right_shift(&self, shift: u64, _key: &()) -> Self {
+        self.with_value(&self.value >> shift)
+    }
+
+    fn scalar_mul(&self, c: &BigInt, _key: &()) -> Self {
+        self.with_value(&self.value * c)
+    }
+
+    fn add(&self, other: &Self, _key: &()) -> Self {
+        self.with_value(&self.value + &other.value)
+    }
+
+    fn sub(&self, other: &Self, _key: &()) -> Self {
+        self.with_value(&self.value - &other.value)
+    }
+
+    fn sub_scalar_if_ge<P: Numeral>(&self, p: P, _key: &()) -> Self {
+        let p = to_bigint(p);
+        match self.value >= p {
+            true => self.with_value(&self.value - p),
+            false => self.clone(),
+        }
+    }
+
+    fn trim_msb(&self, num_blocks: usize, _key: &()) -> Self {
+        Self::from_bigint(
+            self.value.clone(),
+            self.num_blocks - num_blocks,
+            self.bits_per_block,
+        )
+    }
+
+    fn extend_msb(&self, num_blocks: usize, _key: &()) -> Self {
+        Self::from_bigint(
+            self.value.clone(),
+            self.num_blocks + num_blocks,
+            self.bits_per_block,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, Sign};
+    use rand::{rngs::OsRng, Rng};
+    use tfhe::integer::{U256, U512};
+
+    use crate::{
+        helper::{from_bigint, to_bigint},
+        ops::{
+            backend::FheInt,
+            mersenne::{mersenne_coeff, mersenne_mod_native, mod_mersenne_with, MersenneCtx},
+            secp256k1::prelude::FQ_MODULO,
+        },
+    };
+
+    use super::MockInt;
+
+    #[test]
+    fn correct_mock_int_wraps() {
+        // 2 blocks of 2 bits
+        let x = MockInt::new(13u8, 2, 2);
+        assert_eq!(x.value::<u8>(), 13);
+        assert_eq!(x.add(&MockInt::new(5u8, 2, 2), &()).value::<u8>(), 2);
+        assert_eq!(x.sub(&MockInt::new(14u8, 2, 2), &()).value::<u8>(), 15);
+        assert_eq!(x.scalar_left_shift(2, &()).value::<u8>(), 4);
+        assert_eq!(x.trim_msb(1, &()).value::<u8>(), 1);
+        assert_eq!(
+            x.extend_msb(1, &())
+                .scalar_mul(&3.into(), &())
+                .value::<u8>(),
+            39
+        );
+        assert_eq!(x.sub_scalar_if_ge(11u8, &()).value::<u8>(), 2);
+        assert_eq!(x.sub_scalar_if_ge(14u8, &()).value::<u8>(), 13);
+    }
+
+    /// `mod_mersenne_with` of x < 2^(2n) in NB * 2 blocks and of wider x in NB * 3 blocks
+    fn check_mod_mersenne_mock<const NB: usize>(p: u64) {
+        let ctx = MersenneCtx::new(p, 2);
+        let max = (1u64 << (NB * 4)) - 1;
+        for x in [0, 1, (p - 1) * (p - 1), p * p - 1, max] {
+            let res = mod_mersenne_with::<NB, _, _>(&MockInt::new(x, NB * 2, 2), &ctx, &());
+            assert_eq!(res.num_blocks(), NB);
+            assert_eq!(res.value::<u64>(), x % p, "x {} p {}", x, p);
+            if x < p * p {
+                assert_eq!(res.value::<u64>(), mersenne_mod_native(x, p));
+            }
+        }
+        // folded down first
+        for x in [p * p * p + 7, (1u64 << (NB * 6)) - 1] {
+            let res = mod_mersenne_with::<NB, _, _>(&MockInt::new(x, NB * 3, 2), &ctx, &());
+            assert_eq!(res.value::<u64>(), x % p, "x {} p {}", x, p);
+        }
+    }
+
+    #[test]
+    fn correct_mod_mersenne_mock() {
+        check_mod_mersenne_mock::<4>(251);
+        check_mod_mersenne_mock::<8>(65521);
+        check_mod_mersenne_mock::<8>(65519);
+    }
+
+    #[test]
+    fn correct_mod_mersenne_mock_wide_c() {
+        // p = 2^260 - 2^128 - 199, c has 129 bits
+        const NUM_BLOCK: usize = 130;
+        let (_, p_bigint, _, _) = mersenne_coeff(&[260, 128, 199]);
+        let p = from_bigint::<U512>(&p_bigint).unwrap();
+        let ctx = MersenneCtx::new(p, 2);
+        assert!(ctx.c.bits() > 128);
+        for x in [(&p_bigint - 1) << 251, (BigInt::from(1) << 512) - 1] {
+            let value = MockInt::new(from_bigint::<U512>(&x).unwrap(), NUM_BLOCK * 2, 2);
+            let res = mod_mersenne_with::<NUM_BLOCK, _, _>(&value, &ctx, &());
+            assert_eq!(to_bigint(res.value::<U512>()), &x % &p_bigint);
+        }
+    }
+
+    #[test]
+    fn correct_mod_mersenne_mock_secp256k1() {
+        const NUM_BLOCK: usize = 128;
+        let p = *FQ_MODULO;
+        let p_bigint = to_bigint(p);
+        let ctx = MersenneCtx::new(p, 2);
+        let mut cases = vec![
+            BigInt::from(0),
+            &p_bigint * &p_bigint - 1,
+            (BigInt::from(1) << 512) - 1,
+        ];
+        for _ in 0..100 {
+            let bytes = (0..64).map(|_| OsRng.gen::<u8>()).collect::<Vec<_>>();
+            cases.push(BigInt::from_bytes_le(Sign::Plus, &bytes));
+        }
+        for x in cases {
+            let value = from_bigint::<U512>(&x).unwrap();
+            let res = mod_mersenne_with::<NUM_BLOCK, _, _>(
+                &MockInt::new(value, NUM_BLOCK * 2, 2),
+                &ctx,
+                &(),
+            );
+            assert_eq!(to_bigint(res.value::<U256>()), &x % &p_bigint, "x {}", x);
+        }
+    }
+}
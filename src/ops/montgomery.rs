@@ -0,0 +1,242 @@
+use std::time::Instant;
+
+use logging_timer::time;
+use num_bigint::BigInt;
+use tfhe::integer::{IntegerCiphertext, RadixCiphertext, ServerKey};
+
+use crate::{
+    helper::{bits_per_block, from_bigint, to_bigint},
+    numeral::Numeral,
+    stats::{ProtocolLowOps, ProtocolOps, ProtocolStats},
+};
+
+use super::modulo_fast_inner;
+
+/// homomorphic Montgomery reduction t * R^-1 mod n with R = 2^(NB * bits per block)
+/// `n_prime` = -n^-1 mod R from `montgomery_params`
+/// expect t < n * R
+#[time("trace", "Modulus Reduction Montgomery")]
+pub fn montgomery_reduce<const NB: usize, P: Numeral>(
+    t: &RadixCiphertext,
+    n: P,
+    n_prime: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    ProtocolStats::time_op(ProtocolOps::ModReduce, || {
+        montgomery_reduce_inner::<NB, _>(t, n, n_prime, server_key)
+    })
+}
+
+fn montgomery_reduce_inner<const NB: usize, P: Numeral>(
+    t: &RadixCiphertext,
+    n: P,
+    n_prime: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let t = server_key.extend_radix_with_trivial_zero_blocks_msb(
+        t,
+        (NB * 2 + 1).saturating_sub(t.blocks().len()),
+    );
+    // m = (t mod R) * n' mod R
+    let len = t.blocks().len();
+    let mut m = server_key.trim_radix_blocks_msb(&t, len - NB);
+    ProtocolStats::time_op(ProtocolOps::ScalarMul, || {
+        server_key.scalar_mul_assign_parallelized(&mut m, n_prime)
+    });
+    // u = (t + m * n) / R < 2n
+    server_key.extend_radix_with_trivial_zero_blocks_msb_assign(&mut m, len - NB);
+    ProtocolStats::time_op(ProtocolOps::ScalarMul, || {
+        server_key.scalar_mul_assign_parallelized(&mut m, n)
+    });
+    ProtocolStats::time_op(ProtocolOps::Add, || {
+        server_key.add_assign_parallelized(&mut m, &t)
+    });
+    // dividing by R is dropping the NB least significant blocks
+    server_key.trim_radix_blocks_lsb_assign(&mut m, NB);
+    let len = m.blocks().len();
+    server_key.trim_radix_blocks_msb_assign(&mut m, len - (NB + 1));
+
+    modulo_fast_inner::<NB, _>(&m, n, server_key)
+}
+
+/// homomorphic a * b * R^-1 mod n for a, b in Montgomery form
+#[time("debug", "Mul Mod Montgomery")]
+pub fn mul_mod_montgomery<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    n: P,
+    n_prime: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let start_ops = Instant::now();
+    let mut a_expanded = server_key.extend_radix_with_trivial_zero_blocks_msb(a, NB);
+    ProtocolStats::time_op(ProtocolOps::Mul, || {
+        server_key.mul_assign_parallelized(&mut a_expanded, b)
+    });
+    let res = montgomery_reduce::<NB, _>(&a_expanded, n, n_prime, server_key);
+    ProtocolStats::add_time(
+        ProtocolLowOps::MulModMontgomery,
+        start_ops.elapsed().as_secs_f32(),
+    );
+    res
+}
+
+/// homomorphic conversion a -> a * R mod n
+pub fn to_montgomery<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    n: P,
+    n_prime: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    // a * R = REDC(a * R^2)
+    let r2_bits = 2 * NB * bits_per_block(a);
+    let r2 =
+        from_bigint::<P>(&((BigInt::from(1) << r2_bits) % to_bigint(n))).expect("R^2 mod n fits P");
+    let mut a_expanded = server_key.extend_radix_with_trivial_zero_blocks_msb(a, NB);
+    server_key.scalar_mul_assign_parallelized(&mut a_expanded, r2);
+    montgomery_reduce::<NB, _>(&a_expanded, n, n_prime, server_key)
+}
+
+/// homomorphic conversion a * R -> a mod n
+pub fn from_montgomery<const NB: usize, P: Numeral>(
+    a: &RadixCiphertext,
+    n: P,
+    n_prime: P,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    montgomery_reduce::<NB, _>(a, n, n_prime, server_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use rand::{rngs::OsRng, Rng};
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind},
+        shortint::{parameters::PARAM_MESSAGE_4_CARRY_4_KS_PBS, prelude::PARAM_MESSAGE_2_CARRY_2},
+    };
+
+    use crate::{
+        helper::{from_bigint, montgomery_params, to_bigint},
+        ops::{
+            mersenne::mul_mod_mersenne,
+            montgomery::{from_montgomery, mul_mod_montgomery, to_montgomery},
+            native::mul_mod_native,
+        },
+        stats::{ProtocolLowOps, ProtocolOps, ProtocolStats},
+    };
+
+    #[test]
+    fn correct_montgomery_mul_mod() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let n: u8 = 199;
+        let (_r, n_prime) = montgomery_params(&to_bigint(n), NUM_BLOCK, 2);
+        let n_prime: u8 = from_bigint(&n_prime).unwrap();
+        let a = OsRng.gen_range(0..n);
+        let b = OsRng.gen_range(0..n);
+
+        let enc_a = to_montgomery::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(a, NUM_BLOCK),
+            n,
+            n_prime,
+            &server_key,
+        );
+        let enc_b = to_montgomery::<NUM_BLOCK, _>(
+            &client_key.encrypt_radix(b, NUM_BLOCK),
+            n,
+            n_prime,
+            &server_key,
+        );
+        let enc_c = mul_mod_montgomery::<NUM_BLOCK, _>(&enc_a, &enc_b, n, n_prime, &server_key);
+        let enc_c = from_montgomery::<NUM_BLOCK, _>(&enc_c, n, n_prime, &server_key);
+        assert_eq!(
+            mul_mod_native(a, b, n),
+            client_key.decrypt_radix::<u8>(&enc_c)
+        );
+    }
+
+    #[test]
+    fn correct_montgomery_mul_mod_4_bits_blocks() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_4_CARRY_4_KS_PBS, IntegerKeyKind::Radix);
+        // 8 bits in 2 blocks of 4 bits, R = 2^8
+        const NUM_BLOCK: usize = 2;
+        let n: u8 = 199;
+        let (r, n_prime) = montgomery_params(&to_bigint(n), NUM_BLOCK, 4);
+        assert_eq!(r, 256.into());
+        let n_prime: u8 = from_bigint(&n_prime).unwrap();
+
+        for (a, b) in [(n - 1, n - 2), (123, 45), (0, n - 1)] {
+            let enc_a = to_montgomery::<NUM_BLOCK, _>(
+                &client_key.encrypt_radix(a, NUM_BLOCK),
+                n,
+                n_prime,
+                &server_key,
+            );
+            let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+            // (a * R) * b * R^-1 = a * b
+            let enc_c = mul_mod_montgomery::<NUM_BLOCK, _>(&enc_a, &enc_b, n, n_prime, &server_key);
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&enc_c),
+                mul_mod_native(a, b, n)
+            );
+        }
+    }
+
+    /// op counts of one multiplication mod n with each reduction. for n = 251 = 2^8 - 5
+    /// `mod_mersenne` folds twice, for n = 199 = 2^8 - 57 it falls back to Barrett.
+    /// `cargo test --release bench_montgomery_mul_mod -- --nocapture` prints the ratios
+    #[test]
+    fn bench_montgomery_mul_mod() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let ops = [ProtocolOps::Mul, ProtocolOps::ScalarMul, ProtocolOps::Add];
+        let counts =
+            |stats: &ProtocolStats| ops.map(|op| stats.ops.get(&op).map_or(0, |stats| stats.count));
+
+        // [mul, scalar_mul, add] of mersenne then montgomery
+        for (n, mersenne_ops, montgomery_ops) in
+            [(251u8, [1, 2, 4], [1, 2, 1]), (199, [1, 2, 1], [1, 2, 1])]
+        {
+            let (_r, n_prime) = montgomery_params(&to_bigint(n), NUM_BLOCK, 2);
+            let n_prime: u8 = from_bigint(&n_prime).unwrap();
+            let (a, b) = (OsRng.gen_range(0..n), OsRng.gen_range(0..n));
+            let enc_a = client_key.encrypt_radix(a, NUM_BLOCK);
+            let enc_b = client_key.encrypt_radix(b, NUM_BLOCK);
+
+            let now = Instant::now();
+            let (enc_c, mersenne) = ProtocolStats::collect(2, || {
+                mul_mod_mersenne::<NUM_BLOCK, _>(&enc_a, &enc_b, n, &server_key)
+            });
+            let mersenne_time = now.elapsed().as_secs_f64();
+            assert_eq!(
+                client_key.decrypt_radix::<u8>(&enc_c),
+                mul_mod_native(a, b, n)
+            );
+
+            let now = Instant::now();
+            let (_, montgomery) = ProtocolStats::collect(2, || {
+                mul_mod_montgomery::<NUM_BLOCK, _>(&enc_a, &enc_b, n, n_prime, &server_key)
+            });
+            let montgomery_time = now.elapsed().as_secs_f64();
+
+            assert_eq!(counts(&mersenne), mersenne_ops);
+            assert_eq!(counts(&montgomery), montgomery_ops);
+            assert_eq!(montgomery.time[&ProtocolLowOps::MulModMontgomery].0, 1);
+            let total = |ops: [usize; 3]| ops.iter().sum::<usize>() as f64;
+            println!(
+                "n = {}: mersenne {:?} in {:.2}s, montgomery {:?} in {:.2}s, op ratio {:.2}",
+                n,
+                mersenne_ops,
+                mersenne_time,
+                montgomery_ops,
+                montgomery_time,
+                total(montgomery_ops) / total(mersenne_ops)
+            );
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use num_bigint::BigInt;
+use rayon::prelude::{ParallelIterator, ParallelSlice};
 use tfhe::integer::block_decomposition::{DecomposableInto, RecomposableFrom};
 
 use crate::{
-    helper::{from_bigint, to_bigint},
+    helper::{from_bigint_unchecked, to_bigint},
     numeral::Numeral,
 };
 
@@ -12,11 +13,45 @@ pub fn inverse_mod_native<P: Numeral>(a: P, p: P) -> P {
     pow_mod_native(a, p - P::TWO, p)
 }
 
+/// a_i^-1 mod p for all `values` with Montgomery's trick, one inversion and 3 mul mods per value
+/// instead of an inversion each, e.g. to convert many jacobian points to affine.
+/// the values are split into one chunk per rayon thread, each chunk has its own inversion.
+/// all values must be non zero mod p.
+pub fn batch_inverse_native<P: Numeral>(values: &[P], p: P) -> Vec<P> {
+    if values.is_empty() {
+        return vec![];
+    }
+    let chunk_size = values.len().div_ceil(rayon::current_num_threads());
+    values
+        .par_chunks(chunk_size)
+        .flat_map_iter(|chunk| {
+            // prefix[i] = a_0 * .. * a_i
+            let mut prefix = Vec::with_capacity(chunk.len());
+            let mut acc = P::ONE;
+            for value in chunk {
+                acc = mul_mod_native(acc, *value, p);
+                prefix.push(acc);
+            }
+            assert!(acc != P::ZERO, "batch contains a value with no inverse");
+
+            // walk back with inv = (a_0 * .. * a_i)^-1, a_i^-1 = inv * prefix[i - 1]
+            let mut inv = inverse_mod_native(acc, p);
+            let mut inverses = vec![P::ZERO; chunk.len()];
+            for i in (1..chunk.len()).rev() {
+                inverses[i] = mul_mod_native(inv, prefix[i - 1], p);
+                inv = mul_mod_native(inv, chunk[i], p);
+            }
+            inverses[0] = inv;
+            inverses
+        })
+        .collect()
+}
+
 #[inline]
 pub fn modulo_native<P: Numeral>(a: P, p: P) -> P {
     let a_bigint = to_bigint(a);
     let p_bigint = to_bigint(p);
-    from_bigint(&(&a_bigint % &p_bigint))
+    from_bigint_unchecked(&(&a_bigint % &p_bigint))
 }
 
 /// a^b mod p
@@ -38,7 +73,64 @@ pub fn pow_mod_native<P: Numeral>(a: P, b: P, p: P) -> P {
         base = (&base * &base) % &p_bigint;
     }
 
-    from_bigint(&res)
+    from_bigint_unchecked(&res)
+}
+
+/// sqrt(a) mod p using Tonelli-Shanks, `None` if a is not a quadratic residue mod p.
+/// p must be an odd prime
+pub fn sqrt_mod_native<P: Numeral>(a: P, p: P) -> Option<P> {
+    let p_bigint = to_bigint(p);
+    let a_bigint = to_bigint(a) % &p_bigint;
+    let one = BigInt::from(1);
+    if a_bigint == BigInt::from(0) {
+        return Some(P::ZERO);
+    }
+    // euler criterion a^((p-1)/2) = 1
+    let euler = (&p_bigint - &one) >> 1;
+    if a_bigint.modpow(&euler, &p_bigint) != one {
+        return None;
+    }
+
+    // p - 1 = q * 2^s with q odd
+    let mut q = &p_bigint - &one;
+    let mut s = 0;
+    while !q.bit(0) {
+        q >>= 1;
+        s += 1;
+    }
+    // any quadratic non-residue z
+    let mut z = BigInt::from(2);
+    while z.modpow(&euler, &p_bigint) == one {
+        z += 1;
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, &p_bigint);
+    let mut t = a_bigint.modpow(&q, &p_bigint);
+    let mut r = a_bigint.modpow(&((&q + &one) >> 1), &p_bigint);
+    while t != one {
+        // least i such that t^(2^i) = 1
+        let mut i = 0;
+        let mut t2i = t.clone();
+        while t2i != one {
+            t2i = (&t2i * &t2i) % &p_bigint;
+            i += 1;
+        }
+        let b = c.modpow(&(&one << (m - i - 1)), &p_bigint);
+        m = i;
+        c = (&b * &b) % &p_bigint;
+        t = (&t * &c) % &p_bigint;
+        r = (&r * &b) % &p_bigint;
+    }
+
+    Some(from_bigint_unchecked(&r))
+}
+
+/// sqrt(a) mod p = a^((p+1)/4) mod p for p = 3 mod 4.
+/// the result is only a root if a is a quadratic residue, check by squaring it
+#[inline]
+pub fn sqrt_mod_p3mod4_native<P: Numeral>(a: P, p: P) -> P {
+    pow_mod_native(a, (p >> 2) + P::ONE, p)
 }
 
 /// a + b mod p
@@ -46,7 +138,7 @@ pub fn add_mod_native<P: Numeral>(a: P, b: P, p: P) -> P {
     let a_bigint = to_bigint(a);
     let b_bigint = to_bigint(b);
     let p_bigint = to_bigint(p);
-    from_bigint(&((a_bigint + b_bigint) % p_bigint))
+    from_bigint_unchecked(&((a_bigint + b_bigint) % p_bigint))
 }
 
 /// a - b mod p
@@ -55,9 +147,9 @@ pub fn sub_mod_native<P: Numeral>(a: P, b: P, p: P) -> P {
     let b_bigint = to_bigint(b);
     let p_bigint = to_bigint(p);
     if a_bigint < b_bigint {
-        from_bigint(&((a_bigint + p_bigint) - b_bigint))
+        from_bigint_unchecked(&((a_bigint + p_bigint) - b_bigint))
     } else {
-        from_bigint(&((a_bigint - b_bigint) % p_bigint))
+        from_bigint_unchecked(&((a_bigint - b_bigint) % p_bigint))
     }
 }
 
@@ -66,32 +158,34 @@ pub fn mul_mod_native<P: Numeral>(a: P, b: P, p: P) -> P {
     let a_bigint = to_bigint(a);
     let b_bigint = to_bigint(b);
     let p_bigint = to_bigint(p);
-    from_bigint(&((a_bigint * b_bigint) % p_bigint))
+    from_bigint_unchecked(&((a_bigint * b_bigint) % p_bigint))
 }
 
 /// a^2 mod p
 pub fn square_mod_native<P: Numeral>(a: P, p: P) -> P {
     let a_bigint = to_bigint(a);
     let p_bigint = to_bigint(p);
-    from_bigint(&((&a_bigint * &a_bigint) % p_bigint))
+    from_bigint_unchecked(&((&a_bigint * &a_bigint) % p_bigint))
 }
 
 /// a*2 mod p
 pub fn double_mod_native<P: Numeral>(a: P, p: P) -> P {
     let a_bigint = to_bigint(a);
     let p_bigint = to_bigint(p);
-    from_bigint(&((&a_bigint * 2) % p_bigint))
+    from_bigint_unchecked(&((&a_bigint * 2) % p_bigint))
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::{rngs::OsRng, Rng};
     use tfhe::integer::U256;
 
     use crate::{
         helper::u256_from_decimal_string,
         ops::{
             native::{
-                add_mod_native, double_mod_native, inverse_mod_native, pow_mod_native,
+                add_mod_native, batch_inverse_native, double_mod_native, inverse_mod_native,
+                modulo_native, pow_mod_native, sqrt_mod_native, sqrt_mod_p3mod4_native,
                 square_mod_native, sub_mod_native,
             },
             secp256k1::prelude::FQ_MODULO,
@@ -189,4 +283,74 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn correct_batch_inverse_native() {
+        let p: u8 = 251;
+        let values = (1..p).collect::<Vec<_>>();
+        let expected = values
+            .iter()
+            .map(|a| inverse_mod_native(*a, p))
+            .collect::<Vec<_>>();
+        assert_eq!(batch_inverse_native(&values, p), expected);
+        assert_eq!(
+            batch_inverse_native(&[7u8], p),
+            vec![inverse_mod_native(7, p)]
+        );
+        assert_eq!(batch_inverse_native(&[], p), vec![]);
+
+        let p = *FQ_MODULO;
+        let values = (0..100)
+            .map(|_| modulo_native(U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>())), p))
+            .collect::<Vec<_>>();
+        let inverses = batch_inverse_native(&values, p);
+        for (value, inverse) in values.iter().zip(inverses) {
+            assert_eq!(inverse, inverse_mod_native(*value, p));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "batch contains a value with no inverse")]
+    fn reject_batch_inverse_native_zero() {
+        batch_inverse_native(&[3u8, 0, 5], 251);
+    }
+
+    #[test]
+    fn correct_sqrt_mod_native() {
+        // p = 1 mod 4 goes through the full Tonelli-Shanks loop, 241 - 1 = 15 * 2^4
+        for p in [157u8, 241, 211, 251] {
+            for a in 0..p {
+                let residue = (1..p).any(|x| square_mod_native(x, p) == a);
+                match sqrt_mod_native(a, p) {
+                    Some(root) => assert_eq!(square_mod_native(root, p), a),
+                    None => assert!(!residue && a != 0),
+                }
+                if p % 4 == 3 && residue {
+                    let root = sqrt_mod_p3mod4_native(a, p);
+                    assert_eq!(square_mod_native(root, p), a);
+                    let expected = sqrt_mod_native(a, p).unwrap();
+                    assert!(root == expected || root == sub_mod_native(0, expected, p));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn correct_sqrt_mod_native_big() {
+        let p: U256 = *FQ_MODULO;
+        let a: U256 = u256_from_decimal_string(
+            "158972629851468960855479098042189567798917817837573660423710583832714848",
+        );
+        let a2 = square_mod_native(a, p);
+
+        let root = sqrt_mod_native(a2, p).unwrap();
+        assert!(root == a || root == sub_mod_native(U256::ZERO, a, p));
+        assert_eq!(square_mod_native(root, p), a2);
+
+        let fast_root = sqrt_mod_p3mod4_native(a2, p);
+        assert!(fast_root == root || fast_root == sub_mod_native(U256::ZERO, root, p));
+
+        // -1 is not a quadratic residue when p = 3 mod 4
+        assert_eq!(sqrt_mod_native(p - U256::ONE, p), None);
+    }
 }
@@ -1,8 +1,28 @@
+use lazy_static::lazy_static;
+use num_bigint::{BigInt, Sign};
+use tfhe::integer::{RadixCiphertext, ServerKey, U256};
+
+use crate::{
+    helper::to_bigint,
+    ops::{
+        group_jacobian::group_affine_add_native,
+        mersenne::mod_mersenne_fold,
+        mul_mod,
+        native::{mul_mod_native, square_mod_native, sub_mod_native},
+        square_mod,
+    },
+};
+
+use self::prelude::{BETA, FQ_MODULO, FR_MODULO};
+
 pub mod prelude {
     use lazy_static::lazy_static;
     use tfhe::integer::U256;
 
-    use crate::helper::u256_from_decimal_string;
+    use crate::{
+        helper::{u256_from_decimal_string, u256_from_hex_string},
+        ops::curve::CurveParams,
+    };
 
     lazy_static! {
         /// The base prime field modulus of secp256k1
@@ -14,5 +34,316 @@ pub mod prelude {
             u256_from_decimal_string("55066263022277343669578718895168534326250603453777594175500187360389116729240"),
             u256_from_decimal_string("32670510020758816978083085130507043184471273380659243275938904335757337482424"),
         );
+        /// The curve parameters of secp256k1
+        pub static ref PARAMS: CurveParams<U256> = CurveParams {
+            a: U256::ZERO,
+            b: U256::from(7u8),
+            p: *FQ_MODULO,
+            n: *FR_MODULO,
+            generator: *GENERATOR,
+        };
+        /// cube root of unity % n, lambda * (x, y) = (beta * x, y)
+        pub static ref LAMBDA: U256 = u256_from_hex_string("0x5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72");
+        /// cube root of unity % p matching `LAMBDA`
+        pub static ref BETA: U256 = u256_from_hex_string("0x7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee");
+    }
+}
+
+lazy_static! {
+    /// short lattice basis (a1, b1), (a2, b2) of {(x, y) | x + y * lambda = 0 % n}
+    static ref GLV_BASIS: [BigInt; 4] = [
+        BigInt::parse_bytes(b"3086d221a7d46bcde86c90e49284eb15", 16).unwrap(),
+        -BigInt::parse_bytes(b"e4437ed6010e88286f547fa90abfe4c3", 16).unwrap(),
+        BigInt::parse_bytes(b"114ca50f7a8e2f3f657c1108d9d44cfd8", 16).unwrap(),
+        BigInt::parse_bytes(b"3086d221a7d46bcde86c90e49284eb15", 16).unwrap(),
+    ];
+}
+
+/// homomorphic a * b mod n for the secp256k1 group order n = 2^256 - c with a 129 bits c.
+/// `mod_mersenne` needs c below n / 2 = 128 bits, this folds the product 4 times with `mod_mersenne_fold`.
+/// NB * bits per block must be 256, e.g. 128 blocks of `PARAM_MESSAGE_2_CARRY_2`.
+/// `ecdsa::mul_mod_n` is the curve generic multiplication the signing uses.
+pub fn mul_mod_group_order_fold<const NB: usize>(
+    a: &RadixCiphertext,
+    b: &RadixCiphertext,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let mut a_expanded = server_key.extend_radix_with_trivial_zero_blocks_msb(a, NB);
+    server_key.mul_assign_parallelized(&mut a_expanded, b);
+    mod_mersenne_fold::<NB, _>(&a_expanded, *FR_MODULO, server_key)
+}
+
+/// x^(p-2) for the secp256k1 base field p along the addition chain of libsecp256k1.
+/// `square_n(a, k)` is a^(2^k) and x_k below is x^(2^k - 1). p - 2 is 223 ones, a zero,
+/// 22 ones, 4 zeros and 101101 so the long runs of ones are built by doubling the run length.
+fn inverse_addchain<T>(x: &T, square_n: impl Fn(&T, usize) -> T, mul: impl Fn(&T, &T) -> T) -> T {
+    let x2 = mul(&square_n(x, 1), x);
+    let x3 = mul(&square_n(&x2, 1), x);
+    let x6 = mul(&square_n(&x3, 3), &x3);
+    let x9 = mul(&square_n(&x6, 3), &x3);
+    let x11 = mul(&square_n(&x9, 2), &x2);
+    let x22 = mul(&square_n(&x11, 11), &x11);
+    let x44 = mul(&square_n(&x22, 22), &x22);
+    let x88 = mul(&square_n(&x44, 44), &x44);
+    let x176 = mul(&square_n(&x88, 88), &x88);
+    let x220 = mul(&square_n(&x176, 44), &x44);
+    let x223 = mul(&square_n(&x220, 3), &x3);
+
+    let t = mul(&square_n(&x223, 23), &x22);
+    let t = mul(&square_n(&t, 5), x);
+    let t = mul(&square_n(&t, 3), &x2);
+    mul(&square_n(&t, 2), x)
+}
+
+/// homomorphic x^-1 mod p for the secp256k1 base field p with a fixed addition chain.
+/// 255 `square_mod` and 15 `mul_mod` against 255 square and 248 mul for `inverse_mod_fermat`.
+/// expect x != 0
+pub fn inv_mod_addchain<const NB: usize>(
+    x: &RadixCiphertext,
+    server_key: &ServerKey,
+) -> RadixCiphertext {
+    let p = *FQ_MODULO;
+    inverse_addchain(
+        x,
+        |a, k| (0..k).fold(a.clone(), |a, _| square_mod::<NB, _>(&a, p, server_key)),
+        |a, b| mul_mod::<NB, _>(a, b, p, server_key),
+    )
+}
+
+/// native x^-1 mod p for the secp256k1 base field p with the chain of `inv_mod_addchain`
+pub fn inv_mod_addchain_native(x: U256) -> U256 {
+    let p = *FQ_MODULO;
+    inverse_addchain(
+        &x,
+        |a, k| (0..k).fold(*a, |a, _| square_mod_native(a, p)),
+        |a, b| mul_mod_native(*a, *b, p),
+    )
+}
+
+/// split scalar `k` % n into `k1 + k2 * lambda` % n where |k1|, |k2| < 2^128.
+/// k1 and k2 can be negative.
+pub fn glv_decompose_native(k: U256) -> (BigInt, BigInt) {
+    let [a1, b1, a2, b2] = &*GLV_BASIS;
+    let k = to_bigint(k);
+    let n = to_bigint(*FR_MODULO);
+    // c1 = round(b2 * k / n), c2 = round(-b1 * k / n)
+    let c1 = (BigInt::from(2) * b2 * &k + &n) / (BigInt::from(2) * &n);
+    let c2 = (BigInt::from(-2) * b1 * &k + &n) / (BigInt::from(2) * &n);
+    let k1 = &k - &c1 * a1 - &c2 * a2;
+    let k2 = -&c1 * b1 - &c2 * b2;
+
+    (k1, k2)
+}
+
+/// native scalar mul `k * point` on secp256k1 with the GLV endomorphism.
+/// computes `k1 * point + k2 * (beta * x, y)` with a joint double and add over the 128 bit halves,
+/// half the doublings of `scalar_mul_native`.
+pub fn scalar_mul_glv_native(k: U256, point: (U256, U256)) -> (U256, U256) {
+    let p = *FQ_MODULO;
+    let (k1, k2) = glv_decompose_native(k);
+    let neg = |point: (U256, U256)| (point.0, sub_mod_native(U256::ZERO, point.1, p));
+    let endo_point = (mul_mod_native(*BETA, point.0, p), point.1);
+    let (point1, k1) = match k1.sign() == Sign::Minus {
+        true => (neg(point), -k1),
+        false => (point, k1),
+    };
+    let (point2, k2) = match k2.sign() == Sign::Minus {
+        true => (neg(endo_point), -k2),
+        false => (endo_point, k2),
+    };
+
+    let mut res = (U256::ZERO, U256::ZERO);
+    for i in (0..k1.bits().max(k2.bits())).rev() {
+        res = group_affine_add_native(res, res, U256::ZERO, p);
+        if k1.bit(i) {
+            res = group_affine_add_native(res, point1, U256::ZERO, p);
+        }
+        if k2.bit(i) {
+            res = group_affine_add_native(res, point2, U256::ZERO, p);
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand::{rngs::OsRng, Rng};
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind, U256},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use crate::{
+        ecdsa::ecdsa_sign_native,
+        helper::{bigint_to_u256, to_bigint, u256_from_hex_string},
+        ops::{
+            add_mod,
+            group_jacobian::{group_affine_add_native, scalar_mul_native},
+            inverse_mod_fermat,
+            native::{inverse_mod_native, modulo_native, mul_mod_native},
+        },
+    };
+
+    use super::{
+        glv_decompose_native, inv_mod_addchain, inv_mod_addchain_native, inverse_addchain,
+        mul_mod_group_order_fold,
+        prelude::{BETA, FQ_MODULO, FR_MODULO, LAMBDA, PARAMS},
+        scalar_mul_glv_native,
+    };
+
+    #[test]
+    fn correct_mul_mod_n() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 128;
+        let params = *PARAMS;
+        let n = params.n;
+
+        let enc_max = client_key.encrypt_radix(n - U256::ONE, NUM_BLOCK);
+        let res = mul_mod_group_order_fold::<NUM_BLOCK>(&enc_max, &enc_max, &server_key);
+        assert_eq!(client_key.decrypt_radix::<U256>(&res), U256::ONE);
+
+        // s = k^-1 * (m + r * sk) of a real signature
+        let random = || modulo_native(U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>())), n);
+        let (sk, k, message) = (random(), random(), random());
+        let (r, s) = ecdsa_sign_native(sk, k, message, &params);
+        let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+        let enc_r = client_key.encrypt_radix(r, NUM_BLOCK);
+        let enc_k_inv = client_key.encrypt_radix(inverse_mod_native(k, n), NUM_BLOCK);
+        let enc_message = client_key.encrypt_radix(message, NUM_BLOCK);
+
+        let enc_rsk = mul_mod_group_order_fold::<NUM_BLOCK>(&enc_r, &enc_sk, &server_key);
+        let enc_mrsk = add_mod::<NUM_BLOCK, _>(&enc_message, &enc_rsk, n, &server_key);
+        let enc_s = mul_mod_group_order_fold::<NUM_BLOCK>(&enc_k_inv, &enc_mrsk, &server_key);
+        assert_eq!(client_key.decrypt_radix::<U256>(&enc_s), s);
+    }
+
+    #[test]
+    fn correct_inv_mod_addchain_native() {
+        let p = *FQ_MODULO;
+        for x in [U256::ONE, U256::from(2u8), p - U256::ONE] {
+            assert_eq!(inv_mod_addchain_native(x), inverse_mod_native(x, p));
+        }
+        for _ in 0..16 {
+            let x = modulo_native(U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>())), p);
+            if x == U256::ZERO {
+                continue;
+            }
+            assert_eq!(inv_mod_addchain_native(x), inverse_mod_native(x, p));
+        }
+
+        // count the operations on exponents, x^e * x^f = x^(e + f) and (x^e)^2 = x^2e
+        let squares = std::cell::Cell::new(0);
+        let muls = std::cell::Cell::new(0);
+        let exponent = inverse_addchain(
+            &BigInt::from(1),
+            |e, k| {
+                squares.set(squares.get() + k);
+                e << k
+            },
+            |e, f| {
+                muls.set(muls.get() + 1);
+                e + f
+            },
+        );
+        assert_eq!(exponent, to_bigint(p) - 2);
+        assert_eq!((squares.get(), muls.get()), (255, 15));
+    }
+
+    /// 270 mod ops on 128 blocks, run explicitly with
+    /// `cargo test --release correct_inv_mod_addchain -- --ignored`
+    #[test]
+    #[ignore]
+    fn correct_inv_mod_addchain() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 128;
+        let p = *FQ_MODULO;
+        let x = modulo_native(U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>())), p);
+        let enc_x = client_key.encrypt_radix(x, NUM_BLOCK);
+
+        let enc_inv = inv_mod_addchain::<NUM_BLOCK>(&enc_x, &server_key);
+        let enc_fermat = inverse_mod_fermat::<NUM_BLOCK, _>(&enc_x, p, &server_key);
+        assert_eq!(
+            client_key.decrypt_radix::<U256>(&enc_inv),
+            client_key.decrypt_radix::<U256>(&enc_fermat)
+        );
+        assert_eq!(
+            client_key.decrypt_radix::<U256>(&enc_inv),
+            inverse_mod_native(x, p)
+        );
+    }
+
+    #[test]
+    fn correct_glv_endomorphism() {
+        let params = *PARAMS;
+        let (gx, gy) = params.generator;
+        assert_eq!(
+            scalar_mul_native(*LAMBDA, params.generator, &params),
+            (mul_mod_native(*BETA, gx, params.p), gy)
+        );
+    }
+
+    #[test]
+    fn correct_glv_decompose() {
+        let n = to_bigint(*FR_MODULO);
+        let lambda = to_bigint(*LAMBDA);
+        let mut scalars = vec![
+            U256::ONE,
+            *FR_MODULO - U256::ONE,
+            u256_from_hex_string(
+                "0xe9873d79c6d87dc0fb6a5778633389f4453213303da61f20bd67fc233aa33262",
+            ),
+        ];
+        scalars.extend((0..32).map(|_| U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>()))));
+        for k in scalars {
+            let (k1, k2) = glv_decompose_native(k);
+            assert_eq!(
+                ((&k1 + &k2 * &lambda - to_bigint(k)) % &n + &n) % &n,
+                BigInt::from(0)
+            );
+            assert!(k1.bits() <= 128 && k2.bits() <= 128);
+        }
+        assert_eq!(
+            glv_decompose_native(*FR_MODULO - U256::ONE),
+            (BigInt::from(-1), BigInt::from(0))
+        );
+    }
+
+    #[test]
+    fn correct_scalar_mul_glv_native() {
+        let params = *PARAMS;
+        let lambda_g = scalar_mul_native(*LAMBDA, params.generator, &params);
+        for k in [
+            U256::ONE,
+            U256::from(2u8),
+            *FR_MODULO - U256::ONE,
+            u256_from_hex_string(
+                "0xe9873d79c6d87dc0fb6a5778633389f4453213303da61f20bd67fc233aa33262",
+            ),
+            U256::from((OsRng.gen::<u128>(), OsRng.gen::<u128>())),
+        ] {
+            let expected = scalar_mul_native(k, params.generator, &params);
+            assert_eq!(scalar_mul_glv_native(k, params.generator), expected);
+
+            // k1 * G + k2 * lambda * G == k * G
+            let (k1, k2) = glv_decompose_native(k);
+            let n = to_bigint(params.n);
+            let k1 = bigint_to_u256(&((k1 % &n + &n) % &n));
+            let k2 = bigint_to_u256(&((k2 % &n + &n) % &n));
+            let sum = group_affine_add_native(
+                scalar_mul_native(k1, params.generator, &params),
+                scalar_mul_native(k2, lambda_g, &params),
+                params.a,
+                params.p,
+            );
+            assert_eq!(sum, expected);
+        }
+        assert_eq!(
+            scalar_mul_glv_native(U256::ZERO, params.generator),
+            (U256::ZERO, U256::ZERO)
+        );
     }
 }
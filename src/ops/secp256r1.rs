@@ -0,0 +1,27 @@
+/// NIST P-256. the moduli are not Mersenne-like, `mod_mersenne` falls back to Barrett reduction.
+pub mod prelude {
+    use lazy_static::lazy_static;
+    use tfhe::integer::U256;
+
+    use crate::{helper::u256_from_hex_string, ops::curve::CurveParams};
+
+    lazy_static! {
+        /// The base prime field modulus of secp256r1
+        pub static ref FQ_MODULO: U256 = u256_from_hex_string("0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        /// The scalar prime field modulus of secp256r1
+        pub static ref FR_MODULO: U256 = u256_from_hex_string("0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551");
+        /// The prime subgroup generator of secp256r1
+        pub static ref GENERATOR: (U256,U256) = (
+            u256_from_hex_string("0x6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+            u256_from_hex_string("0x4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+        );
+        /// The curve parameters of secp256r1, a = -3
+        pub static ref PARAMS: CurveParams<U256> = CurveParams {
+            a: *FQ_MODULO - U256::from(3u8),
+            b: u256_from_hex_string("0x5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+            p: *FQ_MODULO,
+            n: *FR_MODULO,
+            generator: *GENERATOR,
+        };
+    }
+}
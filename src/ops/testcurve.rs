@@ -0,0 +1,99 @@
+use super::curve::CurveParams;
+
+/// y^2 = x^3 + 29 % 127 of prime order 109 over the 7 bits prime 2^7 - 1, small enough for u8
+/// and 4 blocks of `PARAM_MESSAGE_2_CARRY_2` so tests of the homomorphic protocol run in
+/// seconds. p takes the Mersenne reduction and n the Barrett one.
+pub const PARAMS: CurveParams<u8> = CurveParams {
+    a: 0,
+    b: 29,
+    p: 127,
+    n: 109,
+    generator: (2, 52),
+};
+
+#[cfg(test)]
+mod tests {
+    use tfhe::{
+        integer::{keycache::IntegerKeyCache, IntegerKeyKind},
+        shortint::prelude::PARAM_MESSAGE_2_CARRY_2,
+    };
+
+    use crate::{
+        ecdsa::{
+            assert_signature_valid_native, derive_public_key_native, ecdsa_sign, ecdsa_sign_native,
+            ecdsa_verify_native,
+        },
+        numeral::Numeral,
+        ops::{
+            curve::is_on_curve_native,
+            group_jacobian::{
+                group_projective_into_affine_native, group_projective_scalar_mul_native,
+                scalar_mul_native,
+            },
+        },
+    };
+
+    use super::PARAMS;
+
+    #[test]
+    fn correct_testcurve_scalar_mul() {
+        assert!(is_on_curve_native(PARAMS.generator, &PARAMS));
+        for (k, expected) in [
+            (1, (2, 52)),
+            (2, (21, 107)),
+            (3, (48, 2)),
+            (10, (33, 122)),
+            (108, (2, 75)),
+        ] {
+            assert_eq!(scalar_mul_native(k, PARAMS.generator, &PARAMS), expected);
+            let (x, y, z) = group_projective_scalar_mul_native(
+                PARAMS.generator.0,
+                PARAMS.generator.1,
+                k,
+                PARAMS.p,
+            );
+            assert_eq!(
+                group_projective_into_affine_native(x, y, z, PARAMS.p),
+                expected
+            );
+        }
+        // the generator has order n
+        assert_eq!(
+            scalar_mul_native(PARAMS.n, PARAMS.generator, &PARAMS),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn correct_testcurve_sign_verify_native() {
+        let (sk, k, message) = (77, 35, 89);
+        let pk = derive_public_key_native(sk, &PARAMS);
+        assert_eq!(pk, (29, 65));
+
+        let signature = ecdsa_sign_native(sk, k, message, &PARAMS);
+        assert_eq!(signature, (15, 48));
+        assert_signature_valid_native(pk, message, signature.0, signature.1, &PARAMS);
+        assert!(!ecdsa_verify_native(signature, message + 1, pk, &PARAMS));
+    }
+
+    #[test]
+    fn correct_testcurve_sign_verify() {
+        let (client_key, server_key) =
+            IntegerKeyCache.get_from_params(PARAM_MESSAGE_2_CARRY_2, IntegerKeyKind::Radix);
+        const NUM_BLOCK: usize = 4;
+        let (sk, k, message) = (77, 35, 89);
+
+        let enc_sk = client_key.encrypt_radix(sk, NUM_BLOCK);
+        let enc_k = client_key.encrypt_radix(k, NUM_BLOCK);
+        let (enc_r, enc_s) =
+            ecdsa_sign::<NUM_BLOCK, _>(&enc_sk, &enc_k, message, &PARAMS, &server_key);
+        let signature = (
+            u8::decrypt(&enc_r, &client_key),
+            u8::decrypt(&enc_s, &client_key),
+        );
+
+        assert_eq!(signature, (15, 48));
+        let pk = derive_public_key_native(sk, &PARAMS);
+        assert_signature_valid_native(pk, message, signature.0, signature.1, &PARAMS);
+    }
+}
@@ -0,0 +1,238 @@
+use sha2::{Digest, Sha256};
+use tfhe::integer::U256;
+
+use crate::{
+    helper::{u256_from_be_bytes, u256_to_be_bytes},
+    ops::{
+        curve::CurveParams,
+        group_jacobian::{group_affine_add_native, scalar_mul_native},
+        native::{
+            add_mod_native, modulo_native, mul_mod_native, sqrt_mod_native, square_mod_native,
+        },
+    },
+};
+
+/// BIP340 tagged hash SHA256(SHA256(tag) || SHA256(tag) || data)
+pub fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+fn has_even_y(point: (U256, U256)) -> bool {
+    point.1 & U256::ONE == U256::ZERO
+}
+
+/// the point with x coordinate `x` and an even y on curve `params`, `None` if there is none
+pub fn lift_x_native(x: U256, params: &CurveParams<U256>) -> Option<(U256, U256)> {
+    let p = params.p;
+    if x >= p {
+        return None;
+    }
+    // y^2 = x^3 + a*x + b
+    let x3 = mul_mod_native(square_mod_native(x, p), x, p);
+    let ax = mul_mod_native(params.a, x, p);
+    let y2 = add_mod_native(add_mod_native(x3, ax, p), params.b, p);
+    let y = sqrt_mod_native(y2, p)?;
+    match has_even_y((x, y)) {
+        true => Some((x, y)),
+        false => Some((x, p - y)),
+    }
+}
+
+/// BIP340 x-only public key of secret key `sk` % `n`
+pub fn schnorr_pubkey_native(sk: U256, params: &CurveParams<U256>) -> [u8; 32] {
+    u256_to_be_bytes(scalar_mul_native(sk, params.generator, params).0)
+}
+
+/// e = H_challenge(R.x || P.x || m) % `n`
+fn challenge(r: &[u8], public_key: &[u8], msg: &[u8], r_modulo: U256) -> U256 {
+    let e = tagged_hash("BIP0340/challenge", &[r, public_key, msg]);
+    modulo_native(u256_from_be_bytes(&e), r_modulo)
+}
+
+/// BIP340 Schnorr signature `R.x || s` on `msg` with secret key `sk` in [1, n - 1] and
+/// auxiliary randomness `aux_rand` on curve `params`.
+/// s = k + e * sk % `n` needs no inversion unlike ECDSA's k^-1, only `k * G` stays expensive.
+pub fn schnorr_sign_native(
+    sk: U256,
+    aux_rand: &[u8; 32],
+    msg: &[u8],
+    params: &CurveParams<U256>,
+) -> [u8; 64] {
+    let r_modulo = params.n;
+    assert!(
+        sk != U256::ZERO && sk < r_modulo,
+        "secret key must be in [1, n - 1]"
+    );
+    // negate sk so that the public key P = sk * G has an even y
+    let pk = scalar_mul_native(sk, params.generator, params);
+    let sk = match has_even_y(pk) {
+        true => sk,
+        false => r_modulo - sk,
+    };
+    let pk = u256_to_be_bytes(pk.0);
+
+    // k = H_nonce(sk ^ H_aux(aux_rand) || P.x || m) % `n`
+    let mut t = u256_to_be_bytes(sk);
+    let aux = tagged_hash("BIP0340/aux", &[aux_rand]);
+    t.iter_mut().zip(aux).for_each(|(t, a)| *t ^= a);
+    let k = modulo_native(
+        u256_from_be_bytes(&tagged_hash("BIP0340/nonce", &[&t, &pk, msg])),
+        r_modulo,
+    );
+    assert!(k != U256::ZERO, "nonce is zero");
+    let r_point = scalar_mul_native(k, params.generator, params);
+    let k = match has_even_y(r_point) {
+        true => k,
+        false => r_modulo - k,
+    };
+    let r = u256_to_be_bytes(r_point.0);
+
+    // s = k + e * sk
+    let e = challenge(&r, &pk, msg, r_modulo);
+    let s = add_mod_native(k, mul_mod_native(e, sk, r_modulo), r_modulo);
+
+    let mut signature = [0u8; 64];
+    signature[..32].copy_from_slice(&r);
+    signature[32..].copy_from_slice(&u256_to_be_bytes(s));
+    signature
+}
+
+/// verify BIP340 Schnorr `signature` on `msg` under x-only `public_key` on curve `params`
+pub fn schnorr_verify_native(
+    public_key: &[u8; 32],
+    msg: &[u8],
+    signature: &[u8; 64],
+    params: &CurveParams<U256>,
+) -> bool {
+    let pk = match lift_x_native(u256_from_be_bytes(public_key), params) {
+        Some(pk) => pk,
+        None => return false,
+    };
+    let r = u256_from_be_bytes(&signature[..32]);
+    let s = u256_from_be_bytes(&signature[32..]);
+    if r >= params.p || s >= params.n {
+        return false;
+    }
+
+    // R = s * G - e * P
+    let e = challenge(&signature[..32], public_key, msg, params.n);
+    let r_point = group_affine_add_native(
+        scalar_mul_native(s, params.generator, params),
+        scalar_mul_native(params.n - e, pk, params),
+        params.a,
+        params.p,
+    );
+
+    r_point != (U256::ZERO, U256::ZERO) && has_even_y(r_point) && r_point.0 == r
+}
+
+#[cfg(test)]
+mod tests {
+    use tfhe::integer::U256;
+
+    use crate::{
+        helper::{u256_from_be_bytes, u256_to_be_bytes},
+        ops::secp256k1::prelude::{FQ_MODULO, FR_MODULO, PARAMS},
+    };
+
+    use super::{lift_x_native, schnorr_pubkey_native, schnorr_sign_native, schnorr_verify_native};
+
+    fn bytes<const N: usize>(hex: &str) -> [u8; N] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn correct_schnorr_sign_native() {
+        // BIP340 test vectors 0 to 2, secret key, public key, aux_rand, message, signature
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+            ),
+            (
+                "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+                "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+                "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+            ),
+            (
+                "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+                "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+                "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+                "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+                "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+            ),
+        ];
+        for (sk, pk, aux_rand, msg, signature) in vectors {
+            let sk = u256_from_be_bytes(&bytes::<32>(sk));
+            let pk = bytes::<32>(pk);
+            let msg = bytes::<32>(msg);
+            let signature = bytes::<64>(signature);
+
+            assert_eq!(schnorr_pubkey_native(sk, &PARAMS), pk);
+            assert_eq!(
+                schnorr_sign_native(sk, &bytes(aux_rand), &msg, &PARAMS),
+                signature
+            );
+            assert!(schnorr_verify_native(&pk, &msg, &signature, &PARAMS));
+        }
+    }
+
+    #[test]
+    fn correct_schnorr_verify_native() {
+        // BIP340 test vector 4, verification only
+        assert!(schnorr_verify_native(
+            &bytes("D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9"),
+            &bytes::<32>("4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703"),
+            &bytes("00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4"),
+            &PARAMS
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_schnorr_signature() {
+        let sk = U256::from(3u8);
+        let pk = schnorr_pubkey_native(sk, &PARAMS);
+        let msg = [0u8; 32];
+        let signature = schnorr_sign_native(sk, &[0u8; 32], &msg, &PARAMS);
+        assert!(schnorr_verify_native(&pk, &msg, &signature, &PARAMS));
+
+        // wrong message and tampered s
+        assert!(!schnorr_verify_native(&pk, &[1u8; 32], &signature, &PARAMS));
+        let mut tampered = signature;
+        tampered[63] ^= 1;
+        assert!(!schnorr_verify_native(&pk, &msg, &tampered, &PARAMS));
+
+        // public key not on the curve, BIP340 test vector 5
+        let off_curve = bytes("EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34");
+        assert_eq!(lift_x_native(u256_from_be_bytes(&off_curve), &PARAMS), None);
+        assert!(!schnorr_verify_native(
+            &off_curve, &msg, &signature, &PARAMS
+        ));
+
+        // r = p and s = n are out of range
+        let mut r_overflow = signature;
+        r_overflow[..32].copy_from_slice(&u256_to_be_bytes(*FQ_MODULO));
+        assert!(!schnorr_verify_native(&pk, &msg, &r_overflow, &PARAMS));
+        let mut s_overflow = signature;
+        s_overflow[32..].copy_from_slice(&u256_to_be_bytes(*FR_MODULO));
+        assert!(!schnorr_verify_native(&pk, &msg, &s_overflow, &PARAMS));
+    }
+
+    #[test]
+    #[should_panic(expected = "secret key must be in [1, n - 1]")]
+    fn reject_schnorr_sign_native_zero_key() {
+        schnorr_sign_native(U256::ZERO, &[0u8; 32], &[0u8; 32], &PARAMS);
+    }
+}
@@ -1,18 +1,40 @@
-use std::{collections::BTreeMap, fmt::Display, sync::Mutex};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use lazy_static::lazy_static;
+use rayon::ThreadPoolBuilder;
+use serde_json::{json, Map, Value};
 
 lazy_static! {
-    pub static ref STATS: Mutex<ProtocolStats> = Mutex::new(ProtocolStats {
-        time: BTreeMap::new(),
-        total_time: 0.0,
-    });
+    pub static ref STATS: Mutex<ProtocolStats> = Mutex::new(ProtocolStats::default());
+}
+
+thread_local! {
+    /// accumulator of the `ProtocolStats::collect` pool this thread belongs to
+    static COLLECTOR: RefCell<Option<Arc<Mutex<ProtocolStats>>>> = const { RefCell::new(None) };
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+/// global accumulator of the homomorphic operations, every update goes through the `STATS`
+/// mutex so rayon parallel code paths can record concurrently
+#[derive(Debug, Clone, Default, PartialEq, PartialOrd)]
 pub struct ProtocolStats {
     pub time: BTreeMap<ProtocolLowOps, (usize, f32)>,
+    pub ops: BTreeMap<ProtocolOps, OpStats>,
     pub total_time: f32,
+    /// first record since the last reset
+    pub started: Option<Instant>,
+}
+
+/// number of calls and cumulative time in seconds of one operation category
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct OpStats {
+    pub count: usize,
+    pub time: f32,
 }
 
 impl Display for ProtocolStats {
@@ -41,6 +63,7 @@ pub enum ProtocolLowOps {
     SubMod,
     DoubleMod,
     MulMod,
+    MulModMontgomery,
     SquareMod,
     InverseMod,
 }
@@ -52,26 +75,274 @@ impl Display for ProtocolLowOps {
             ProtocolLowOps::SubMod => f.write_str("Sub Mod"),
             ProtocolLowOps::DoubleMod => f.write_str("Double Mod"),
             ProtocolLowOps::MulMod => f.write_str("Mul Mod"),
+            ProtocolLowOps::MulModMontgomery => f.write_str("Mul Mod Montgomery"),
             ProtocolLowOps::SquareMod => f.write_str("Square Mod"),
             ProtocolLowOps::InverseMod => f.write_str("Inverse Mod"),
         }
     }
 }
 
+/// radix level operation categories, finer than `ProtocolLowOps`.
+/// `ModReduce` covers a whole reduction including the scalar muls and adds it is made of,
+/// once per `mod_mersenne`, `mod_barrett`, `montgomery_reduce` or `modulo_fast` call. the final
+/// subtraction a reduction ends with is part of it, not a second `ModReduce`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProtocolOps {
+    Mul,
+    ScalarMul,
+    Add,
+    ModReduce,
+}
+
+impl Display for ProtocolOps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolOps::Mul => f.write_str("mul"),
+            ProtocolOps::ScalarMul => f.write_str("scalar_mul"),
+            ProtocolOps::Add => f.write_str("add"),
+            ProtocolOps::ModReduce => f.write_str("mod_reduce"),
+        }
+    }
+}
+
 impl ProtocolStats {
     pub fn add_time(op: ProtocolLowOps, time: f32) {
-        let mut stat = STATS.lock().unwrap();
-        let entry = stat.time.entry(op).or_insert((0, 0.0));
-        *entry = (entry.0 + 1, entry.1 + time);
-        stat.total_time += time;
+        STATS.lock().unwrap().record_time(op, time);
+        with_collector(|stats| stats.record_time(op, time));
     }
 
+    /// zero all counters e.g. between two benchmark runs
     pub fn reset() {
-        let mut stat = STATS.lock().unwrap();
-        stat.time.clear();
+        STATS.lock().unwrap().clear();
     }
 
     pub fn stats() -> Self {
         STATS.lock().unwrap().clone()
     }
+
+    pub fn add_op_time(op: ProtocolOps, time: f32) {
+        STATS.lock().unwrap().record_op(op, time);
+        with_collector(|stats| stats.record_op(op, time));
+    }
+
+    /// run `f` on a dedicated rayon pool of `num_threads` threads and return the operations it
+    /// recorded, including the ones of the rayon tasks it spawns. they are recorded in `STATS`
+    /// as usual, the returned stats only hold those of `f` whatever runs concurrently, e.g.
+    /// exact op counts in tests running in parallel.
+    pub fn collect<T: Send>(num_threads: usize, f: impl FnOnce() -> T + Send) -> (T, Self) {
+        let collector = Arc::new(Mutex::new(ProtocolStats::default()));
+        let thread_collector = collector.clone();
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .start_handler(move |_| {
+                COLLECTOR.with(|c| *c.borrow_mut() = Some(thread_collector.clone()));
+            })
+            .build()
+            .expect("build the stats thread pool");
+        let res = pool.install(f);
+        let stats = collector.lock().unwrap().clone();
+        (res, stats)
+    }
+
+    /// run `f` and record its elapsed time under `op`
+    pub fn time_op<T>(op: ProtocolOps, f: impl FnOnce() -> T) -> T {
+        let start_ops = Instant::now();
+        let res = f();
+        ProtocolStats::add_op_time(op, start_ops.elapsed().as_secs_f32());
+        res
+    }
+
+    /// count and cumulative time per operation category keyed by `mul`, `scalar_mul`, `add`
+    /// and `mod_reduce`
+    pub fn stats_by_op() -> HashMap<String, OpStats> {
+        STATS.lock().unwrap().by_op()
+    }
+
+    /// the current counters as a JSON object with the Unix `timestamp` in seconds, `total_time`
+    /// and the `wall_time` in seconds since the first record, `low_ops` keyed by the
+    /// `ProtocolLowOps` names and `ops` keyed like `stats_by_op`
+    pub fn to_json() -> String {
+        STATS.lock().unwrap().json().to_string()
+    }
+
+    fn json(&self) -> Value {
+        let op_json = |count: usize, time: f32| json!({ "count": count, "time": time });
+        let low_ops = self
+            .time
+            .iter()
+            .map(|(op, (count, time))| (op.to_string(), op_json(*count, *time)))
+            .collect::<Map<_, _>>();
+        let ops = self
+            .ops
+            .iter()
+            .map(|(op, stats)| (op.to_string(), op_json(stats.count, stats.time)))
+            .collect::<Map<_, _>>();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let wall_time = self
+            .started
+            .map_or(0.0, |started| started.elapsed().as_secs_f32());
+
+        json!({
+            "timestamp": timestamp,
+            "total_time": self.total_time,
+            "wall_time": wall_time,
+            "low_ops": low_ops,
+            "ops": ops,
+        })
+    }
+
+    fn record_time(&mut self, op: ProtocolLowOps, time: f32) {
+        self.started.get_or_insert_with(Instant::now);
+        let entry = self.time.entry(op).or_insert((0, 0.0));
+        *entry = (entry.0 + 1, entry.1 + time);
+        self.total_time += time;
+    }
+
+    fn clear(&mut self) {
+        *self = ProtocolStats::default();
+    }
+
+    fn record_op(&mut self, op: ProtocolOps, time: f32) {
+        self.started.get_or_insert_with(Instant::now);
+        let entry = self.ops.entry(op).or_default();
+        entry.count += 1;
+        entry.time += time;
+    }
+
+    /// count and cumulative time per operation category like `stats_by_op`, e.g. of the stats
+    /// returned by `collect`
+    pub fn by_op(&self) -> HashMap<String, OpStats> {
+        self.ops
+            .iter()
+            .map(|(op, stats)| (op.to_string(), *stats))
+            .collect()
+    }
+}
+
+fn with_collector(f: impl FnOnce(&mut ProtocolStats)) {
+    COLLECTOR.with(|c| {
+        if let Some(collector) = c.borrow().as_ref() {
+            f(&mut collector.lock().unwrap());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+    use serde_json::Value;
+
+    use super::{OpStats, ProtocolLowOps, ProtocolOps, ProtocolStats};
+
+    #[test]
+    fn correct_stats_by_op() {
+        let mut stats = ProtocolStats::default();
+        // one mersenne mul mod
+        stats.record_op(ProtocolOps::Mul, 1.0);
+        for _ in 0..2 {
+            stats.record_op(ProtocolOps::ScalarMul, 0.5);
+            stats.record_op(ProtocolOps::Add, 0.25);
+            stats.record_op(ProtocolOps::Add, 0.25);
+        }
+        stats.record_op(ProtocolOps::ModReduce, 2.5);
+
+        let by_op = stats.by_op();
+        assert_eq!(by_op.len(), 4);
+        assert_eq!(
+            by_op["mul"],
+            OpStats {
+                count: 1,
+                time: 1.0
+            }
+        );
+        assert_eq!(
+            by_op["scalar_mul"],
+            OpStats {
+                count: 2,
+                time: 1.0
+            }
+        );
+        assert_eq!(
+            by_op["add"],
+            OpStats {
+                count: 4,
+                time: 1.0
+            }
+        );
+        assert_eq!(
+            by_op["mod_reduce"],
+            OpStats {
+                count: 1,
+                time: 2.5
+            }
+        );
+    }
+
+    #[test]
+    fn correct_stats_concurrent_record() {
+        // task i records (i + 1) * 100 operations through the global `STATS`, on 8 threads
+        let contributions = (1..=8).map(|i| i * 100).collect::<Vec<usize>>();
+        let before = ProtocolStats::stats();
+        let ((), stats) = ProtocolStats::collect(8, || {
+            contributions.par_iter().for_each(|&records| {
+                for _ in 0..records {
+                    ProtocolStats::add_time(ProtocolLowOps::InverseMod, 0.5);
+                    ProtocolStats::time_op(ProtocolOps::Mul, thread::yield_now);
+                }
+            });
+        });
+        let after = ProtocolStats::stats();
+
+        // the collected stats hold exactly the records of the 8 tasks
+        let total = contributions.iter().sum::<usize>();
+        assert_eq!(
+            stats.time[&ProtocolLowOps::InverseMod],
+            (total, total as f32 * 0.5)
+        );
+        assert_eq!(stats.total_time, total as f32 * 0.5);
+        assert_eq!(stats.ops[&ProtocolOps::Mul].count, total);
+        // the global stats got all of them too, plus whatever other tests ran meanwhile
+        let count = |stats: &ProtocolStats| stats.ops.get(&ProtocolOps::Mul).map_or(0, |s| s.count);
+        assert!(count(&after) - count(&before) >= total);
+    }
+
+    #[test]
+    fn correct_stats_clear() {
+        let mut stats = ProtocolStats::default();
+        stats.record_time(ProtocolLowOps::AddMod, 1.0);
+        stats.record_op(ProtocolOps::Add, 1.0);
+        stats.clear();
+        assert_eq!(stats, ProtocolStats::default());
+        assert_eq!(stats.total_time, 0.0);
+        assert!(stats.by_op().is_empty());
+    }
+
+    #[test]
+    fn correct_stats_json() {
+        let mut stats = ProtocolStats::default();
+        let json: Value = serde_json::from_str(&stats.json().to_string()).unwrap();
+        assert_eq!(json["wall_time"], 0.0);
+        assert!(json["ops"].as_object().unwrap().is_empty());
+
+        stats.record_time(ProtocolLowOps::MulMod, 1.5);
+        stats.record_time(ProtocolLowOps::MulMod, 0.5);
+        stats.record_time(ProtocolLowOps::AddMod, 0.25);
+        stats.record_op(ProtocolOps::Mul, 1.0);
+        stats.record_op(ProtocolOps::ModReduce, 0.75);
+
+        let json: Value = serde_json::from_str(&stats.json().to_string()).unwrap();
+        assert!(json["timestamp"].as_u64().unwrap() > 0);
+        assert!(json["wall_time"].as_f64().unwrap() >= 0.0);
+        assert_eq!(json["total_time"], 2.25);
+        assert_eq!(json["low_ops"]["Mul Mod"]["count"], 2);
+        assert_eq!(json["low_ops"]["Mul Mod"]["time"], 2.0);
+        assert_eq!(json["low_ops"]["Add Mod"]["count"], 1);
+        assert_eq!(json["ops"]["mul"]["count"], 1);
+        assert_eq!(json["ops"]["mod_reduce"]["time"], 0.75);
+        assert!(json["ops"].get("add").is_none());
+    }
 }